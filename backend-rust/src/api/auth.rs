@@ -0,0 +1,114 @@
+use actix_web::{dev::Payload, web, FromRequest, HttpRequest, Responder, Scope};
+use std::future::{ready, Ready};
+
+use crate::domain::models::{LoginRequest, LoginResponse, Role};
+use crate::error::{ApiResponse, AppError, AppResult};
+use crate::service::auth_service::AuthService;
+use crate::service::user_service::UserService;
+
+// --- Handler ---
+
+/// Authenticates a username/password pair and mints a bearer token on success.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Login succeeded", body = LoginResponse),
+        (status = 401, description = "Invalid username or password"),
+    )
+)]
+pub(crate) async fn login(
+    user_service: web::Data<UserService>,
+    auth_service: web::Data<AuthService>,
+    req: web::Json<LoginRequest>,
+) -> AppResult<impl Responder> {
+    let LoginRequest {
+        user_name,
+        password,
+    } = req.into_inner();
+    tracing::info!(user_name, "Handler: Received login request");
+
+    let user = user_service.authenticate(&user_name, &password).await?;
+    let token = auth_service.generate_token(user.id, &user.user_name, user.role)?;
+
+    tracing::info!(user_id = user.id, "Handler: Login succeeded");
+    Ok(ApiResponse::success(LoginResponse { token }))
+}
+
+// --- Extractor ---
+
+/// Resolved identity of the caller, extracted from a validated
+/// `Authorization: Bearer <token>` header. Adding this as a handler argument
+/// gates the route behind authentication.
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser {
+    pub user_id: i64,
+    pub user_name: String,
+    pub role: Role,
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(extract_authenticated_user(req))
+    }
+}
+
+fn extract_authenticated_user(req: &HttpRequest) -> AppResult<AuthenticatedUser> {
+    let auth_service = req
+        .app_data::<web::Data<AuthService>>()
+        .ok_or_else(|| AppError::Internal("AuthService is not configured".to_string()))?;
+
+    let header_value = req
+        .headers()
+        .get(actix_web::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| AppError::Unauthorized("Missing Authorization header".to_string()))?;
+
+    let token = header_value.strip_prefix("Bearer ").ok_or_else(|| {
+        AppError::Unauthorized("Authorization header must be a Bearer token".to_string())
+    })?;
+
+    let claims = auth_service.validate_token(token)?;
+    Ok(AuthenticatedUser {
+        user_id: claims.sub,
+        user_name: claims.user_name,
+        role: claims.role,
+    })
+}
+
+/// Resolved identity of an `Admin`-role caller. Adding this as a handler
+/// argument instead of `AuthenticatedUser` gates the route behind both
+/// authentication and the `Admin` role, rejecting with `AppError::Forbidden`
+/// (403) when the caller is authenticated but not an admin.
+#[derive(Debug, Clone)]
+pub struct RequireAdmin(pub AuthenticatedUser);
+
+impl FromRequest for RequireAdmin {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let result = extract_authenticated_user(req).and_then(|user| {
+            if user.role == Role::Admin {
+                Ok(RequireAdmin(user))
+            } else {
+                Err(AppError::Forbidden(
+                    "This action requires the admin role".to_string(),
+                ))
+            }
+        });
+        ready(result)
+    }
+}
+
+// --- Route Configuration ---
+
+/// Configures the routes for the auth API endpoints under the `/auth` scope.
+pub fn configure_auth_api() -> Scope {
+    web::scope("/auth").route("/login", web::post().to(login))
+}
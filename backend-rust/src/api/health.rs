@@ -1,8 +1,10 @@
 use actix_web::{web, HttpResponse, Responder, Scope};
 use sqlx::PgPool;
+use std::sync::Mutex;
+use std::time::Instant;
 use time::OffsetDateTime;
 
-use crate::domain::models::HealthStatus;
+use crate::domain::models::{HealthStatus, LivenessStatus};
 use crate::error::AppResult;
 
 // State to hold server start time
@@ -11,13 +13,84 @@ pub struct ServerStartTime {
     pub time: OffsetDateTime,
 }
 
-// --- Handler ---
+/// Holds the `sysinfo::System` handle used to sample the current process's
+/// memory/CPU usage for healthchecks. Kept behind a `Mutex` and reused
+/// across requests instead of building a fresh `System` per call, since
+/// initializing one is a relatively expensive full-process-table scan.
+pub struct HealthMonitor {
+    sys: Mutex<sysinfo::System>,
+    pid: sysinfo::Pid,
+}
+
+impl HealthMonitor {
+    pub fn new() -> Self {
+        let pid = sysinfo::get_current_pid().expect("failed to resolve current process id");
+        Self {
+            sys: Mutex::new(sysinfo::System::new()),
+            pid,
+        }
+    }
+
+    /// Refreshes the current process's stats and builds a `HealthStatus` from them.
+    #[allow(clippy::too_many_arguments)]
+    fn collect(
+        &self,
+        db_ok: bool,
+        db_latency_ms: Option<u64>,
+        pool_size: u32,
+        pool_idle: usize,
+        uptime_duration: Option<time::Duration>,
+    ) -> HealthStatus {
+        let mut sys = self.sys.lock().unwrap();
+        sys.refresh_process(self.pid);
+        HealthStatus::collect(db_ok, db_latency_ms, pool_size, pool_idle, uptime_duration, &sys, self.pid)
+    }
+}
+
+impl Default for HealthMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// --- Handlers ---
+
+/// Reports that the process is up and answering requests. Never touches the
+/// database, so it stays cheap and stays `200 OK` even while a dependency is
+/// down; container orchestrators should use this only to decide whether to
+/// restart the process, not whether to route traffic to it (that's
+/// `/health/ready`).
+#[utoipa::path(
+    get,
+    path = "/api/v1/health/live",
+    tag = "health",
+    responses(
+        (status = 200, description = "Process is alive", body = LivenessStatus),
+    )
+)]
+pub(crate) async fn get_liveness(start_time_data: web::Data<ServerStartTime>) -> AppResult<impl Responder> {
+    let uptime = OffsetDateTime::now_utc() - start_time_data.time;
+    Ok(HttpResponse::Ok().json(LivenessStatus::new(Some(uptime))))
+}
 
-async fn get_api_status(
+/// Reports whether the service's dependencies are usable, so orchestrators
+/// can gate traffic on it. Returns `503` when the database probe fails.
+#[utoipa::path(
+    get,
+    path = "/api/v1/health/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "Service is ready to serve traffic", body = HealthStatus),
+        (status = 503, description = "A dependency (e.g. the database) is unavailable", body = HealthStatus),
+    )
+)]
+pub(crate) async fn get_readiness(
     pool: web::Data<PgPool>,                     // Inject PgPool directly
     start_time_data: web::Data<ServerStartTime>, // Inject start time state
+    health_monitor: web::Data<HealthMonitor>,    // Inject sysinfo sampler
 ) -> AppResult<impl Responder> {
-    // Check DB connection
+    // Check DB connection, timing the round trip for db_latency_ms
+    let probe_started = Instant::now();
     let db_ready = match sqlx::query("SELECT 1").fetch_one(&**pool).await {
         Ok(_) => true,
         Err(e) => {
@@ -25,14 +98,16 @@ async fn get_api_status(
             false
         }
     };
+    let db_latency_ms = db_ready.then(|| probe_started.elapsed().as_millis() as u64);
 
     // Calculate uptime
     let uptime = OffsetDateTime::now_utc() - start_time_data.time;
 
-    let status_response = HealthStatus::new(db_ready, Some(uptime));
+    let status_response =
+        health_monitor.collect(db_ready, db_latency_ms, pool.size(), pool.num_idle(), Some(uptime));
 
     // Return 503 if DB is not ready, otherwise 200
-    let mut http_status = if db_ready {
+    let mut http_status = if status_response.is_ready() {
         HttpResponse::Ok()
     } else {
         HttpResponse::ServiceUnavailable()
@@ -43,5 +118,8 @@ async fn get_api_status(
 
 // Function to configure routes for this module
 pub fn configure_health_api() -> Scope {
-    web::scope("/health").route("", web::get().to(get_api_status))
+    web::scope("/health")
+        .route("", web::get().to(get_readiness))
+        .route("/live", web::get().to(get_liveness))
+        .route("/ready", web::get().to(get_readiness))
 }
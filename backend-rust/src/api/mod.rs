@@ -1,4 +1,6 @@
+pub mod auth;
 pub mod health;
+pub mod openapi;
 pub mod users;
 
 use actix_web::web;
@@ -7,6 +9,7 @@ use actix_web::web;
 pub fn configure_api(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/v1")
+            .service(auth::configure_auth_api())
             .service(users::configure_users_api())
             .service(health::configure_health_api()),
     );
@@ -0,0 +1,68 @@
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::api::{auth, health, users};
+use crate::domain::models::{
+    HealthStatus, LivenessStatus, LoginRequest, LoginResponse, PagedResponse, Permission, User,
+    UserCreateRequest, UserCreateRequestData, UserPatchRequest, UserPatchRequestData, UserStatus,
+    UserUpdateRequest, UserUpdateRequestData,
+};
+
+/// Registers the `bearer_auth` security scheme used by every protected route.
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+        );
+    }
+}
+
+/// Aggregates the API's handler and schema definitions into a single OpenAPI 3.0 document.
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        health::get_liveness,
+        health::get_readiness,
+        users::list_users,
+        users::get_user,
+        users::create_user,
+        users::update_user,
+        users::patch_user,
+        users::delete_user,
+        auth::login,
+    ),
+    components(schemas(
+        User,
+        UserCreateRequest,
+        UserCreateRequestData,
+        UserUpdateRequest,
+        UserUpdateRequestData,
+        UserPatchRequest,
+        UserPatchRequestData,
+        UserStatus,
+        Permission,
+        PagedResponse<User>,
+        HealthStatus,
+        LivenessStatus,
+        LoginRequest,
+        LoginResponse,
+    )),
+    tags(
+        (name = "users", description = "User management endpoints"),
+        (name = "health", description = "Service health checks"),
+        (name = "auth", description = "Authentication endpoints"),
+    ),
+    modifiers(&SecurityAddon)
+)]
+pub struct ApiDoc;
+
+/// Serves the generated spec at `/api-docs/openapi.json` and an interactive
+/// Swagger UI at `/swagger-ui/`.
+pub fn configure_openapi_docs() -> SwaggerUi {
+    SwaggerUi::new("/swagger-ui/{_:.*}").url("/api-docs/openapi.json", ApiDoc::openapi())
+}
@@ -1,24 +1,70 @@
 use actix_web::{web, HttpResponse, Responder, Scope};
 // Import the specific request/response types needed
-use crate::domain::models::{UserCreateRequest, UserUpdateRequest}; // Added User
-use crate::error::AppResult;
+use crate::api::auth::{AuthenticatedUser, RequireAdmin};
+use crate::domain::models::{
+    PagedResponse, User, UserCreateRequest, UserListQuery, UserPatchRequest, UserUpdateRequest,
+};
+use crate::error::{ApiResponse, AppResult};
 use crate::service::user_service::UserService; // Import the concrete service
 
 // --- Handlers ---
+// Every handler below takes an `AuthenticatedUser` (read endpoints) or a
+// `RequireAdmin` (mutating endpoints), which Actix resolves by validating
+// the caller's bearer token before the handler body runs. The route rejects
+// the request with `AppError::Unauthorized` if that fails, or with
+// `AppError::Forbidden` if the caller is authenticated but not an admin.
 
-/// List all users currently in the system.
-async fn list_users(
+/// List users currently in the system, paginated and optionally filtered/sorted.
+#[utoipa::path(
+    get,
+    path = "/api/v1/users",
+    tag = "users",
+    params(
+        ("page" = Option<u32>, Query, description = "Page number, 1-based (default 1)"),
+        ("perPage" = Option<u32>, Query, description = "Rows per page, clamped to 100 (default 20)"),
+        ("sort" = Option<String>, Query, description = "userName|email|createdAt, prefix with '-' for descending"),
+        ("q" = Option<String>, Query, description = "Case-insensitive search across userName, firstName, lastName, email"),
+    ),
+    responses(
+        (status = 200, description = "A page of users", body = PagedResponse<User>),
+        (status = 401, description = "Missing or invalid bearer token"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub(crate) async fn list_users(
+    _caller: AuthenticatedUser,
     // Inject the UserService instance via application state
     user_service: web::Data<UserService>,
+    query: web::Query<UserListQuery>,
 ) -> AppResult<impl Responder> {
-    tracing::info!("Handler: Received request to list users");
-    let users = user_service.list_users().await?;
-    tracing::debug!("Handler: Found {} users", users.len());
-    Ok(HttpResponse::Ok().json(users))
+    let query = query.into_inner();
+    tracing::info!(q = ?query.q, sort = ?query.sort, "Handler: Received request to list users");
+    let mut page = user_service.list_users(query).await?;
+    tracing::debug!(
+        "Handler: Found {} users (page {}, {} total)",
+        page.data.len(),
+        page.page,
+        page.total
+    );
+    page.data = page.data.into_iter().map(User::with_permissions).collect();
+    Ok(ApiResponse::success(page))
 }
 
 /// Get a specific user by their unique ID.
-async fn get_user(
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{id}",
+    tag = "users",
+    params(("id" = i64, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "The requested user", body = User),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 404, description = "No user with that ID"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub(crate) async fn get_user(
+    _caller: AuthenticatedUser,
     user_service: web::Data<UserService>,
     // Extract the user ID from the URL path
     path: web::Path<i64>,
@@ -27,11 +73,26 @@ async fn get_user(
     tracing::info!(user_id = user_id, "Handler: Received request to get user");
     let user = user_service.get_user(user_id).await?;
     tracing::debug!(user_id = user_id, "Handler: Found user");
-    Ok(HttpResponse::Ok().json(user))
+    Ok(ApiResponse::success(user.with_permissions()))
 }
 
 /// Create a new user in the system.
-async fn create_user(
+#[utoipa::path(
+    post,
+    path = "/api/v1/users",
+    tag = "users",
+    request_body = UserCreateRequest,
+    responses(
+        (status = 201, description = "The newly created user", body = User),
+        (status = 400, description = "Missing or invalid fields"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 409, description = "userName or email already taken"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub(crate) async fn create_user(
+    _caller: RequireAdmin,
     user_service: web::Data<UserService>,
     // Automatically deserialize JSON payload into UserCreateRequest
     // Handles basic JSON parsing errors (returns 400 Bad Request)
@@ -49,11 +110,28 @@ async fn create_user(
         "Handler: User created successfully"
     );
     // Return 201 Created status code with the created user object
-    Ok(HttpResponse::Created().json(created_user))
+    Ok(HttpResponse::Created().json(ApiResponse::success(created_user.with_permissions())))
 }
 
 /// Update an existing user by their ID.
-async fn update_user(
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/{id}",
+    tag = "users",
+    params(("id" = i64, Path, description = "User ID")),
+    request_body = UserUpdateRequest,
+    responses(
+        (status = 200, description = "The updated user", body = User),
+        (status = 400, description = "Missing or invalid fields"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "No user with that ID"),
+        (status = 409, description = "userName or email already taken"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub(crate) async fn update_user(
+    _caller: RequireAdmin,
     user_service: web::Data<UserService>,
     path: web::Path<i64>,
     req: web::Json<UserUpdateRequest>,
@@ -69,11 +147,63 @@ async fn update_user(
         user_id = updated_user.id,
         "Handler: User updated successfully"
     );
-    Ok(HttpResponse::Ok().json(updated_user))
+    Ok(ApiResponse::success(updated_user.with_permissions()))
+}
+
+/// Partially update an existing user by their ID (PATCH). Unlike `PUT
+/// /users/{id}`, only fields present in the request body are changed: an
+/// omitted key leaves that field untouched, while an explicit JSON `null`
+/// for `department` clears it.
+#[utoipa::path(
+    patch,
+    path = "/api/v1/users/{id}",
+    tag = "users",
+    params(("id" = i64, Path, description = "User ID")),
+    request_body = UserPatchRequest,
+    responses(
+        (status = 200, description = "The patched user", body = User),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "No user with that ID"),
+        (status = 409, description = "userName or email already taken"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub(crate) async fn patch_user(
+    _caller: RequireAdmin,
+    user_service: web::Data<UserService>,
+    path: web::Path<i64>,
+    req: web::Json<UserPatchRequest>,
+) -> AppResult<impl Responder> {
+    let user_id = path.into_inner();
+    let patch_request = req.into_inner();
+    tracing::info!(user_id = user_id, "Handler: Received request to patch user");
+
+    let patched_user = user_service.patch_user(user_id, patch_request).await?;
+
+    tracing::info!(
+        user_id = patched_user.id,
+        "Handler: User patched successfully"
+    );
+    Ok(ApiResponse::success(patched_user.with_permissions()))
 }
 
 /// Delete a user by their unique ID.
-async fn delete_user(
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/{id}",
+    tag = "users",
+    params(("id" = i64, Path, description = "User ID")),
+    responses(
+        (status = 204, description = "User deleted"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "No user with that ID"),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub(crate) async fn delete_user(
+    _caller: RequireAdmin,
     user_service: web::Data<UserService>,
     path: web::Path<i64>,
 ) -> AppResult<impl Responder> {
@@ -86,7 +216,8 @@ async fn delete_user(
     user_service.delete_user(user_id).await?;
 
     tracing::info!(user_id = user_id, "Handler: User deleted successfully");
-    // Return 204 No Content status code on successful deletion
+    // 204 No Content has no body by definition, so there's nothing for the
+    // success/data envelope to wrap here.
     Ok(HttpResponse::NoContent().finish())
 }
 
@@ -99,5 +230,6 @@ pub fn configure_users_api() -> Scope {
         .route("", web::post().to(create_user)) // POST /users
         .route("/{id}", web::get().to(get_user)) // GET /users/{id}
         .route("/{id}", web::put().to(update_user)) // PUT /users/{id}
+        .route("/{id}", web::patch().to(patch_user)) // PATCH /users/{id}
         .route("/{id}", web::delete().to(delete_user)) // DELETE /users/{id}
 }
@@ -4,6 +4,8 @@ use serde::Deserialize;
 use std::path::PathBuf;
 use tracing;
 
+use crate::logging::LogFormat;
+
 // Combined Config struct using Clap for CLI/Env and Serde for file loading
 #[derive(Debug, Clone, Deserialize, Parser)]
 #[clap(name = "user-management-backend", version, about, long_about = None)]
@@ -16,11 +18,28 @@ pub struct Config {
     #[serde(flatten)]
     pub db: DbConfig,
 
+    #[clap(flatten)]
+    #[serde(flatten)]
+    pub auth: AuthConfig,
+
     /// Enable verbose output (increase level with more flags: -v, -vv, -vvv)
     #[clap(short, long, action = clap::ArgAction::Count, env = "APP_VERBOSE")]
     #[serde(default)] // Default to 0 if not in file/env
     pub verbose: u8,
 
+    /// Apply pending database migrations then exit, without starting the HTTP server.
+    #[clap(long, env = "APP_MIGRATE_ONLY")]
+    #[serde(default)]
+    pub migrate_only: bool,
+
+    /// Log output format for the structured tracing subscriber: `pretty`
+    /// (human-friendly, default), `compact` (single-line human-friendly), or
+    /// `json` (machine-parseable; every line logged while handling a request
+    /// carries that request's `request_id`, set by `TracingLogger`).
+    #[clap(long, env = "APP_LOG_FORMAT", value_enum, default_value = "pretty")]
+    #[serde(default = "default_log_format")]
+    pub log_format: LogFormat,
+
     /// Load configuration from a specific TOML file
     #[clap(short, long, value_name = "FILE", env = "APP_CONFIG_FILE")]
     #[serde(skip)] // Don't expect 'config_file' field in the config file itself
@@ -45,7 +64,9 @@ pub struct HttpConfig {
     #[serde(default = "default_http_workers")]
     pub workers: usize,
 
-    // Rate limit example - not used directly by Actix core, needs middleware
+    /// Per-client-IP request budget, in requests per minute, enforced by
+    /// `middleware::rate_limit::RateLimiter` (wrapped around the app in
+    /// `main.rs`).
     #[clap(long, env = "APP_HTTP_RATE_LIMIT", default_value_t = 100)]
     #[serde(default = "default_http_rate_limit")]
     pub rate_limit: usize,
@@ -54,16 +75,81 @@ pub struct HttpConfig {
 #[derive(Debug, Clone, Deserialize, Parser)]
 #[group(id = "db")]
 pub struct DbConfig {
-    /// Database connection string (DSN)
+    /// High-privilege DSN (DDL: CREATE/ALTER/GRANT) used only to apply schema
+    /// migrations at startup. Should authenticate as a `migration_user` role,
+    /// never the low-privilege role the running service uses per request.
+    /// Recommended: Set via MIGRATION_DATABASE_URL environment variable
+    #[clap(long, env = "MIGRATION_DATABASE_URL")]
+    #[serde(default = "default_migration_dsn")]
+    pub migration_dsn: String,
+
+    /// Low-privilege DSN (DML only: SELECT/INSERT/UPDATE/DELETE) that
+    /// `UserRepository` uses to serve requests. Should authenticate as the
+    /// `service` role so a compromised request path cannot alter schema.
     /// Recommended: Set via DATABASE_URL environment variable
     #[clap(long, env = "DATABASE_URL")]
-    #[serde(default = "default_db_dsn")] // Provide a fallback default
-    pub dsn: String,
+    #[serde(default = "default_runtime_dsn")] // Provide a fallback default
+    pub runtime_dsn: String,
 
-    /// Maximum number of connections in the database pool
+    /// Maximum number of connections in the runtime database pool
     #[clap(long, env = "DATABASE_MAX_OPEN_CONNS", default_value_t = 10)] // Use DATABASE_ prefix
     #[serde(default = "default_db_max_open_conns")]
     pub max_open_conns: u32,
+
+    /// Whether startup applies pending schema migrations (via
+    /// `database::run_migrations`) before serving traffic. Separate from
+    /// `--migrate-only`, which always migrates and exits regardless of this
+    /// flag; this one only gates the normal startup path.
+    #[clap(long, env = "APP_DB_AUTO_MIGRATE", action = clap::ArgAction::Set, default_value_t = true)]
+    #[serde(default = "default_db_auto_migrate")]
+    pub auto_migrate: bool,
+
+    /// Path to a PEM-encoded CA certificate bundle to trust for Postgres
+    /// TLS connections, in addition to (not instead of) the system trust
+    /// store. Use this to connect to a server presenting a private-CA or
+    /// self-signed certificate without disabling verification entirely.
+    /// Mutually exclusive with `--db-tls-insecure-skip-verify`.
+    #[clap(long, env = "APP_DB_CA_CERT")]
+    #[serde(default)]
+    pub ca_cert: Option<PathBuf>,
+
+    /// Skip Postgres TLS certificate verification (`sslmode=require`
+    /// semantics: the connection is still encrypted, but the server's
+    /// certificate is not checked against any CA). Local/dev use against
+    /// self-signed certs only; never enable in production. Mutually
+    /// exclusive with `--db-ca-cert`.
+    #[clap(long, env = "APP_DB_TLS_INSECURE_SKIP_VERIFY")]
+    #[serde(default)]
+    pub tls_insecure_skip_verify: bool,
+
+    /// Path to a file holding the database password, mounted out-of-band
+    /// (e.g. a Docker/Kubernetes secret) rather than embedded in
+    /// `runtime_dsn`/`migration_dsn`. Spliced into both DSNs by
+    /// `load_config` after all other layering. Mutually exclusive with an
+    /// inline password in either DSN.
+    #[clap(long, env = "APP_DB_PASSWORD_FILE")]
+    #[serde(default)]
+    pub password_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize, Parser)]
+#[group(id = "auth")]
+pub struct AuthConfig {
+    /// Secret key used to sign and verify JWTs (HS256).
+    /// Must be overridden in any non-dev environment.
+    #[clap(long, env = "APP_AUTH_JWT_SECRET", default_value = "dev-only-change-me")]
+    #[serde(default = "default_jwt_secret")]
+    pub jwt_secret: String,
+
+    /// Lifetime of an issued JWT, in seconds, before it expires.
+    #[clap(long, env = "APP_AUTH_JWT_MAX_AGE", default_value_t = 3600)]
+    #[serde(default = "default_jwt_max_age")]
+    pub jwt_max_age: u64,
+
+    /// Issuer string embedded in and checked against issued JWTs.
+    #[clap(long, env = "APP_AUTH_ISSUER", default_value = "user-management-demo")]
+    #[serde(default = "default_jwt_issuer")]
+    pub issuer: String,
 }
 
 // Default value functions for serde (used if field missing in config file/env)
@@ -80,13 +166,35 @@ fn default_http_workers() -> usize {
 fn default_http_rate_limit() -> usize {
     100
 }
-fn default_db_dsn() -> String {
+fn default_runtime_dsn() -> String {
     // Sensible default for local dev, but encourage override via DATABASE_URL
     "postgresql://localhost:5432/template1?sslmode=disable".to_string()
 }
+fn default_migration_dsn() -> String {
+    // Local dev default; production must point this at a `migration_user`
+    // with DDL rights, set via MIGRATION_DATABASE_URL.
+    "postgresql://localhost:5432/template1?sslmode=disable".to_string()
+}
 fn default_db_max_open_conns() -> u32 {
     10
 } // Default pool size
+fn default_db_auto_migrate() -> bool {
+    true
+}
+fn default_jwt_secret() -> String {
+    // Dev-only fallback; every real deployment must override this via
+    // APP_AUTH_JWT_SECRET so tokens can't be forged with a published default.
+    "dev-only-change-me".to_string()
+}
+fn default_jwt_max_age() -> u64 {
+    3600 // 1 hour
+}
+fn default_jwt_issuer() -> String {
+    "user-management-demo".to_string()
+}
+fn default_log_format() -> LogFormat {
+    LogFormat::Pretty
+}
 
 // --- Loading Logic ---
 
@@ -145,31 +253,93 @@ pub fn load_config() -> Result<Config, ConfigError> {
     if cli_args.http.port != default_cli_args.http.port {
         cfg.http.port = cli_args.http.port;
     }
-    if cli_args.db.dsn != default_cli_args.db.dsn && !cli_args.db.dsn.is_empty() {
+    if cli_args.db.runtime_dsn != default_cli_args.db.runtime_dsn
+        && !cli_args.db.runtime_dsn.is_empty()
+    {
         // Ensure CLI DSN isn't just the default empty string from clap if not set
-        cfg.db.dsn = cli_args.db.dsn;
+        cfg.db.runtime_dsn = cli_args.db.runtime_dsn;
+    }
+    if cli_args.db.migration_dsn != default_cli_args.db.migration_dsn
+        && !cli_args.db.migration_dsn.is_empty()
+    {
+        cfg.db.migration_dsn = cli_args.db.migration_dsn;
     }
     if cli_args.db.max_open_conns != default_cli_args.db.max_open_conns {
         cfg.db.max_open_conns = cli_args.db.max_open_conns;
     }
+    if cli_args.db.auto_migrate != default_cli_args.db.auto_migrate {
+        cfg.db.auto_migrate = cli_args.db.auto_migrate;
+    }
+    if cli_args.db.ca_cert != default_cli_args.db.ca_cert {
+        cfg.db.ca_cert = cli_args.db.ca_cert;
+    }
+    if cli_args.db.tls_insecure_skip_verify {
+        cfg.db.tls_insecure_skip_verify = true;
+    }
+    if cli_args.db.password_file != default_cli_args.db.password_file {
+        cfg.db.password_file = cli_args.db.password_file;
+    }
+    if cli_args.auth.jwt_secret != default_cli_args.auth.jwt_secret {
+        cfg.auth.jwt_secret = cli_args.auth.jwt_secret;
+    }
+    if cli_args.auth.jwt_max_age != default_cli_args.auth.jwt_max_age {
+        cfg.auth.jwt_max_age = cli_args.auth.jwt_max_age;
+    }
+    if cli_args.auth.issuer != default_cli_args.auth.issuer {
+        cfg.auth.issuer = cli_args.auth.issuer;
+    }
 
     // Verbosity: Use the highest value provided (CLI flag overrides lower env/file)
     if cli_args.verbose > cfg.verbose {
         cfg.verbose = cli_args.verbose;
     }
 
-    // Final check: Ensure DATABASE_URL env var (if set directly, not via APP_DB_DSN)
-    // takes precedence over everything else for the DSN.
+    // migrate_only is a simple opt-in flag: true from any source wins
+    if cli_args.migrate_only {
+        cfg.migrate_only = true;
+    }
+    if cli_args.log_format != default_cli_args.log_format {
+        cfg.log_format = cli_args.log_format;
+    }
+
+    // Final check: Ensure the raw env vars (if set directly, not via the APP_ prefix)
+    // take precedence over everything else for the DSNs.
     if let Ok(db_url_env) = std::env::var("DATABASE_URL") {
         if !db_url_env.is_empty() {
-            cfg.db.dsn = db_url_env;
+            cfg.db.runtime_dsn = db_url_env;
         }
     }
+    if let Ok(migration_url_env) = std::env::var("MIGRATION_DATABASE_URL") {
+        if !migration_url_env.is_empty() {
+            cfg.db.migration_dsn = migration_url_env;
+        }
+    }
+
+    // 6. Splice in a password read from `db.password_file`, if set, after all
+    // other layering has resolved both DSNs.
+    if let Some(password_file) = &cfg.db.password_file {
+        let password = std::fs::read_to_string(password_file).map_err(|e| {
+            ConfigError::Message(format!(
+                "Failed to read db.password_file '{}': {}",
+                password_file.display(),
+                e
+            ))
+        })?;
+        let password = password.trim();
+        cfg.db.runtime_dsn = splice_dsn_password(&cfg.db.runtime_dsn, password)?;
+        cfg.db.migration_dsn = splice_dsn_password(&cfg.db.migration_dsn, password)?;
+    }
 
-    // 6. Final Validation
-    if cfg.db.dsn.is_empty() {
+    // 7. Final Validation
+    if cfg.db.runtime_dsn.is_empty() {
+        return Err(ConfigError::Message(
+            "Runtime database DSN ('db.runtime_dsn' or DATABASE_URL) must be set.".into(),
+        ));
+    }
+    if cfg.db.migration_dsn.is_empty() {
         return Err(ConfigError::Message(
-            "Database DSN ('db.dsn' or DATABASE_URL) must be set.".into(),
+            "Migration database DSN ('db.migration_dsn' or MIGRATION_DATABASE_URL) must be set."
+                .into(),
         ));
     }
     if cfg.db.max_open_conns == 0 {
@@ -177,11 +347,37 @@ pub fn load_config() -> Result<Config, ConfigError> {
             "'db.max_open_conns' must be greater than 0.".into(),
         ));
     }
+    if cfg.db.ca_cert.is_some() && cfg.db.tls_insecure_skip_verify {
+        return Err(ConfigError::Message(
+            "'db.ca_cert' and 'db.tls_insecure_skip_verify' are mutually exclusive.".into(),
+        ));
+    }
 
     // Log the final loaded configuration (mask sensitive info like DSN password)
     let mut logged_cfg = cfg.clone();
-    logged_cfg.db.dsn = crate::database::mask_dsn_password(&logged_cfg.db.dsn);
+    logged_cfg.db.runtime_dsn = crate::database::mask_dsn_password(&logged_cfg.db.runtime_dsn);
+    logged_cfg.db.migration_dsn =
+        crate::database::mask_dsn_password(&logged_cfg.db.migration_dsn);
+    logged_cfg.auth.jwt_secret = "***".to_string();
     tracing::debug!(final_config = ?logged_cfg, "Final configuration loaded");
 
     Ok(cfg)
 }
+
+/// Splices `password` into `dsn` via `url::Url::set_password`. Errors if
+/// `dsn` already carries an inline password — `--db-password-file`'s whole
+/// point is keeping the password out of the DSN, so having both set is a
+/// configuration mistake rather than something to silently resolve.
+fn splice_dsn_password(dsn: &str, password: &str) -> Result<String, ConfigError> {
+    let mut url = url::Url::parse(dsn)
+        .map_err(|e| ConfigError::Message(format!("Invalid database DSN: {}", e)))?;
+    if url.password().is_some() {
+        return Err(ConfigError::Message(
+            "db.password_file is set but the DSN already has an inline password; set only one."
+                .into(),
+        ));
+    }
+    url.set_password(Some(password))
+        .map_err(|_| ConfigError::Message("Failed to set password on database DSN".into()))?;
+    Ok(url.to_string())
+}
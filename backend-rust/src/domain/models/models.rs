@@ -1,41 +1,308 @@
-use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use regex::Regex;
+use serde::{de, Deserialize, Serialize};
+use sqlx::encode::IsNull;
+use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef};
+use sqlx::sqlite::{Sqlite, SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef};
+use sqlx::{Decode, Encode, FromRow, Postgres};
 use time::OffsetDateTime;
+use utoipa::openapi::{ObjectBuilder, RefOr, Schema, SchemaType};
+use utoipa::ToSchema;
+use validator::{Validate, ValidationErrors, ValidationErrorsKind};
+
+// Username charset + length are enforced by a single regex rather than
+// separate `regex` and `length` validators, so a too-short or too-long
+// username reports as an invalid format rather than a length error.
+lazy_static::lazy_static! {
+    static ref USERNAME_REGEX: Regex = Regex::new(r"^[A-Za-z0-9_]{4,32}$").unwrap();
+}
+
+// --- Role Enum ---
+
+/// Authorization role granted to a user. Controls access to mutating
+/// endpoints via the `RequireAdmin` extractor — see `api::auth`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "user_role", rename_all = "lowercase")]
+pub enum Role {
+    Admin,
+    User,
+}
+
+impl Default for Role {
+    /// New users get the least-privileged role unless explicitly promoted.
+    fn default() -> Self {
+        Role::User
+    }
+}
+
+impl Role {
+    fn code(self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::User => "user",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "admin" => Some(Role::Admin),
+            "user" => Some(Role::User),
+            _ => None,
+        }
+    }
+}
+
+// Postgres stores `role` in a dedicated `user_role` enum type (handled by the
+// `#[sqlx(type_name = "user_role")]` derive above). SQLite has no equivalent
+// named-enum concept, so `SqliteUserRepository` needs its own TEXT-based
+// codec here, the same way `UserStatus` already does for both backends.
+impl sqlx::Type<Sqlite> for Role {
+    fn type_info() -> SqliteTypeInfo {
+        <&str as sqlx::Type<Sqlite>>::type_info()
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for Role {
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let code = <&str as Decode<Sqlite>>::decode(value)?;
+        Role::from_code(code).ok_or_else(|| format!("invalid role code in database: {:?}", code).into())
+    }
+}
+
+impl<'q> Encode<'q, Sqlite> for Role {
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+        <&str as Encode<Sqlite>>::encode_by_ref(&self.code(), buf)
+    }
+}
+
+// --- Permission Enum ---
+
+/// A single well-known permission a `Role` can grant. Kept as a closed enum
+/// rather than a free-form string (or a `Permission` DB table) so the set of
+/// valid permissions is fixed at compile time, matching `Role`'s own design.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum Permission {
+    #[serde(rename = "user:create")]
+    UserCreate,
+    #[serde(rename = "user:read")]
+    UserRead,
+    #[serde(rename = "user:update")]
+    UserUpdate,
+    #[serde(rename = "user:delete")]
+    UserDelete,
+}
+
+impl Role {
+    /// The effective permission set granted by this role. `Admin` gets every
+    /// known permission; `User` gets read-only access, matching the
+    /// mutate-requires-`RequireAdmin` gating already enforced at the route
+    /// layer — this just makes that policy visible to API consumers.
+    pub fn permissions(self) -> Vec<Permission> {
+        match self {
+            Role::Admin => vec![
+                Permission::UserCreate,
+                Permission::UserRead,
+                Permission::UserUpdate,
+                Permission::UserDelete,
+            ],
+            Role::User => vec![Permission::UserRead],
+        }
+    }
+}
+
+// --- UserStatus Enum ---
+
+/// Lifecycle status of a user account, stored as the single-character code
+/// "A"/"I"/"T" in the `users.user_status` column and on the wire. Custom
+/// `Serialize`/`Deserialize` impls (rather than `#[serde(rename = ...)]`)
+/// let deserialization reject any other code with a clear error instead of
+/// silently accepting it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserStatus {
+    Active,
+    Inactive,
+    Terminated,
+}
+
+impl UserStatus {
+    /// The single-character code stored in the database and sent over the wire.
+    pub fn code(self) -> &'static str {
+        match self {
+            UserStatus::Active => "A",
+            UserStatus::Inactive => "I",
+            UserStatus::Terminated => "T",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "A" => Some(UserStatus::Active),
+            "I" => Some(UserStatus::Inactive),
+            "T" => Some(UserStatus::Terminated),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for UserStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for UserStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let code = String::deserialize(deserializer)?;
+        UserStatus::from_code(&code)
+            .ok_or_else(|| de::Error::unknown_variant(&code, &["A", "I", "T"]))
+    }
+}
+
+// sqlx maps UserStatus onto the same wire format: `type_info`/`compatible`
+// delegate to Postgres's text type, and `Decode`/`Encode` translate through
+// the single-character codes, so the enum round-trips the `user_status`
+// column without a dedicated Postgres enum type.
+impl sqlx::Type<Postgres> for UserStatus {
+    fn type_info() -> PgTypeInfo {
+        <String as sqlx::Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <String as sqlx::Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for UserStatus {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let code = <&str as Decode<Postgres>>::decode(value)?;
+        UserStatus::from_code(code)
+            .ok_or_else(|| format!("invalid user_status code in database: {:?}", code).into())
+    }
+}
+
+impl<'q> Encode<'q, Postgres> for UserStatus {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        <&str as Encode<Postgres>>::encode_by_ref(&self.code(), buf)
+    }
+}
+
+// Same single-character-code mapping as the Postgres impls above, for
+// `SqliteUserRepository`.
+impl sqlx::Type<Sqlite> for UserStatus {
+    fn type_info() -> SqliteTypeInfo {
+        <&str as sqlx::Type<Sqlite>>::type_info()
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for UserStatus {
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let code = <&str as Decode<Sqlite>>::decode(value)?;
+        UserStatus::from_code(code)
+            .ok_or_else(|| format!("invalid user_status code in database: {:?}", code).into())
+    }
+}
+
+impl<'q> Encode<'q, Sqlite> for UserStatus {
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+        <&str as Encode<Sqlite>>::encode_by_ref(&self.code(), buf)
+    }
+}
+
+impl<'s> ToSchema<'s> for UserStatus {
+    fn schema() -> (&'s str, RefOr<Schema>) {
+        (
+            "UserStatus",
+            ObjectBuilder::new()
+                .schema_type(SchemaType::String)
+                .enum_values(Some(["A", "I", "T"]))
+                .description(Some(
+                    "Account status: A (active), I (inactive), T (terminated)",
+                ))
+                .into(),
+        )
+    }
+}
 
 // --- User Model ---
 /// Represents a user entity in the database and API responses.
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 #[serde(rename_all = "camelCase")] // JSON fields are camelCase
 pub struct User {
     /// Unique identifier for the user (Database generated).
     #[sqlx(rename = "user_id")] // Map db column user_id to this field
+    #[schema(example = 1)]
     pub id: i64,
 
     /// Unique username for login.
+    #[schema(example = "jdoe")]
     pub user_name: String,
 
     /// User's first name.
+    #[schema(example = "Jane")]
     pub first_name: String,
 
     /// User's last name.
+    #[schema(example = "Doe")]
     pub last_name: String,
 
     /// User's email address (must be unique).
+    #[schema(format = Email, example = "jane.doe@example.com")]
     pub email: String,
 
-    /// Current status of the user account (e.g., "A", "I", "T").
-    pub user_status: String,
+    /// Current status of the user account.
+    pub user_status: UserStatus,
 
     /// Optional department the user belongs to.
+    #[schema(example = "Engineering")]
     pub department: Option<String>,
 
+    /// Authorization role, defaults to `Role::User` for newly created accounts.
+    pub role: Role,
+
     /// Timestamp when the user was created (UTC).
     #[serde(with = "time::serde::rfc3339")]
+    #[schema(value_type = String, format = DateTime, example = "2024-01-01T12:00:00Z")]
     pub created_at: OffsetDateTime,
 
     /// Timestamp when the user was last updated (UTC).
     #[serde(with = "time::serde::rfc3339")]
+    #[schema(value_type = String, format = DateTime, example = "2024-01-01T12:00:00Z")]
     pub updated_at: OffsetDateTime,
+
+    /// Effective permission set granted by `role`, flattened for API
+    /// consumers. Not a database column: always empty as loaded from a row
+    /// (`#[sqlx(default)]`) and filled in via `with_permissions` before the
+    /// user is serialized into a response.
+    #[serde(skip_deserializing)]
+    #[sqlx(default)]
+    pub permissions: Vec<Permission>,
+}
+
+impl User {
+    /// Populates `permissions` from `role`. Handlers call this on every
+    /// `User` right before it goes into a JSON response.
+    pub fn with_permissions(mut self) -> Self {
+        self.permissions = self.role.permissions();
+        self
+    }
+}
+
+// --- Credential Model ---
+/// Stores the Argon2id PHC hash string backing a user's password, kept
+/// separate from `User` so it is never accidentally serialized into API
+/// responses. The PHC string embeds its own salt and cost parameters, so no
+/// separate salt column is needed.
+#[derive(Debug, Clone, FromRow)]
+pub struct Credential {
+    pub user_id: i64,
+    /// Argon2id PHC hash string, e.g. `$argon2id$v=19$m=...,t=...,p=...$salt$hash`.
+    pub hash: String,
 }
 
 // --- Request Payloads ---
@@ -45,94 +312,489 @@ pub struct User {
 // Using #[serde(flatten)] makes the JSON structure flat as expected by the API.
 
 /// Data required for creating a new user.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct UserCreateRequestData {
+    /// Unique username for login.
+    #[validate(required, regex(path = *USERNAME_REGEX, message = "must be 4-32 characters: letters, digits, underscore"))]
+    #[schema(example = "jdoe")]
     pub user_name: Option<String>, // Use Option for potentially better validation messages later
+    #[validate(required, length(min = 1, max = 64, message = "must be 1-64 characters"))]
+    #[schema(example = "Jane")]
     pub first_name: Option<String>,
+    #[validate(required, length(min = 1, max = 64, message = "must be 1-64 characters"))]
+    #[schema(example = "Doe")]
     pub last_name: Option<String>,
+    /// User's email address; must be a valid, unique email.
+    #[validate(required, email(message = "must be a valid email address"))]
+    #[schema(format = Email, example = "jane.doe@example.com")]
     pub email: Option<String>,
-    pub user_status: Option<String>, // String status like "A", "I", "T"
-    pub department: Option<String>,  // Optional field
+    /// Account status, one of "A" (active), "I" (inactive), "T" (terminated).
+    pub user_status: Option<UserStatus>,
+    #[validate(length(max = 128, message = "must be at most 128 characters"))]
+    #[schema(example = "Engineering")]
+    pub department: Option<String>, // Optional field
+
+    /// Plaintext password for the new account. Never echoed back in a response.
+    #[serde(skip_serializing)]
+    #[schema(example = "hunter2_better_password")]
+    pub password: Option<String>,
 }
 
 /// Request body for creating a new user.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
 pub struct UserCreateRequest {
     // Flatten wraps the inner struct's fields directly into this struct for JSON/serde
     #[serde(flatten)]
+    #[validate(nested)]
     pub data: UserCreateRequestData,
 }
 
+impl UserCreateRequest {
+    /// Runs `data`'s field validators and flattens the nested `data.*` error
+    /// tree into a flat `(field, message)` list, so callers get plain field
+    /// names instead of `validator`'s `Struct("data", ...)` wrapper.
+    pub fn validate_flat(&self) -> Vec<(&'static str, String)> {
+        match self.validate() {
+            Ok(()) => Vec::new(),
+            Err(errors) => flatten_validation_errors(&errors),
+        }
+    }
+}
+
 /// Data required for updating an existing user. All fields are mandatory for a PUT request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct UserUpdateRequestData {
+    #[validate(required, regex(path = *USERNAME_REGEX, message = "must be 4-32 characters: letters, digits, underscore"))]
+    #[schema(example = "jdoe")]
     pub user_name: Option<String>,
+    #[validate(required, length(min = 1, max = 64, message = "must be 1-64 characters"))]
+    #[schema(example = "Jane")]
     pub first_name: Option<String>,
+    #[validate(required, length(min = 1, max = 64, message = "must be 1-64 characters"))]
+    #[schema(example = "Doe")]
     pub last_name: Option<String>,
+    #[validate(required, email(message = "must be a valid email address"))]
+    #[schema(format = Email, example = "jane.doe@example.com")]
     pub email: Option<String>,
-    pub user_status: Option<String>, // String status like "A", "I", "T"
+    pub user_status: Option<UserStatus>,
+    #[validate(length(max = 128, message = "must be at most 128 characters"))]
+    #[schema(example = "Engineering")]
     pub department: Option<String>,
+
+    /// New plaintext password, if the caller wants to change it. Never echoed back.
+    #[serde(skip_serializing)]
+    #[schema(example = "hunter2_better_password")]
+    pub password: Option<String>,
 }
 
 /// Request body for updating an existing user (PUT).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
 pub struct UserUpdateRequest {
     #[serde(flatten)]
+    #[validate(nested)]
     pub data: UserUpdateRequestData,
 }
 
-// --- Healthcheck Response Model ---
-/// Represents the health status of the API and its dependencies.
-#[derive(Debug, Serialize, Deserialize)]
+impl UserUpdateRequest {
+    /// See `UserCreateRequest::validate_flat`.
+    pub fn validate_flat(&self) -> Vec<(&'static str, String)> {
+        match self.validate() {
+            Ok(()) => Vec::new(),
+            Err(errors) => flatten_validation_errors(&errors),
+        }
+    }
+}
+
+/// Flattens a nested `validator::ValidationErrors` tree (as produced by
+/// `#[validate(nested)]` on the wrapping `data` field) into a flat list of
+/// `(field, message)` pairs, so API responses don't leak the `data.*` nesting.
+fn flatten_validation_errors(errors: &ValidationErrors) -> Vec<(&'static str, String)> {
+    let mut flat = Vec::new();
+    for (field, kind) in errors.errors() {
+        match kind {
+            ValidationErrorsKind::Field(field_errors) => {
+                for e in field_errors {
+                    let message = e.message.clone().map(|m| m.to_string()).unwrap_or_else(|| e.code.to_string());
+                    flat.push((*field, message));
+                }
+            }
+            ValidationErrorsKind::Struct(nested) => flat.extend(flatten_validation_errors(nested)),
+            ValidationErrorsKind::List(list) => {
+                for nested in list.values() {
+                    flat.extend(flatten_validation_errors(nested));
+                }
+            }
+        }
+    }
+    flat
+}
+
+// --- Undefinable / Patch Request ---
+
+/// Distinguishes "the client didn't send this key" from "the client sent an
+/// explicit `null`" for PATCH fields that are themselves nullable (e.g.
+/// `User::department`). A plain `Option<T>` can't tell these apart once
+/// deserialized, which collapses "leave it alone" and "clear it" into the
+/// same value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Undefinable<T> {
+    /// The key was absent from the JSON body: leave the field untouched.
+    Missing,
+    /// The key was present and set to `null`: clear the field.
+    Null,
+    /// The key was present with a value: set the field to it.
+    Value(T),
+}
+
+impl<T> Default for Undefinable<T> {
+    fn default() -> Self {
+        Undefinable::Missing
+    }
+}
+
+impl<T> Undefinable<T> {
+    /// Used as `#[serde(skip_serializing_if = "Undefinable::is_missing")]` so
+    /// an untouched field is simply absent from serialized output.
+    pub fn is_missing(&self) -> bool {
+        matches!(self, Undefinable::Missing)
+    }
+}
+
+// `#[serde(default)]` on the field already covers the "key absent" case by
+// falling back to `Default::default()` (i.e. `Missing`) without ever calling
+// this impl, so deserialization only has to distinguish `null` from a value.
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Undefinable<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(match Option::<T>::deserialize(deserializer)? {
+            Some(value) => Undefinable::Value(value),
+            None => Undefinable::Null,
+        })
+    }
+}
+
+impl<T: Serialize> Serialize for Undefinable<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Undefinable::Value(value) => value.serialize(serializer),
+            Undefinable::Null | Undefinable::Missing => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<'s, T: ToSchema<'s>> ToSchema<'s> for Undefinable<T> {
+    fn schema() -> (&'s str, RefOr<Schema>) {
+        T::schema()
+    }
+}
+
+/// Data accepted by the PATCH endpoint. Every field is optional, and
+/// `department` additionally distinguishes "omit" from "set to null" via
+/// `Undefinable`, since it's the one field on `User` that's itself nullable.
+/// Unlike `UserUpdateRequestData`, fields here are never declared `required`:
+/// the whole point of PATCH is that the caller only sends what's changing.
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UserPatchRequestData {
+    #[validate(regex(path = *USERNAME_REGEX, message = "must be 4-32 characters: letters, digits, underscore"))]
+    #[schema(example = "jdoe")]
+    pub user_name: Option<String>,
+    #[validate(length(min = 1, max = 64, message = "must be 1-64 characters"))]
+    #[schema(example = "Jane")]
+    pub first_name: Option<String>,
+    #[validate(length(min = 1, max = 64, message = "must be 1-64 characters"))]
+    #[schema(example = "Doe")]
+    pub last_name: Option<String>,
+    #[validate(email(message = "must be a valid email address"))]
+    #[schema(format = Email, example = "jane.doe@example.com")]
+    pub email: Option<String>,
+    pub user_status: Option<UserStatus>,
+    // `Undefinable<String>` doesn't implement `validator::Validate` (it's a
+    // three-state custom type, not a plain `Option`), so it carries no
+    // `#[validate(...)]` attribute here; its length check instead runs by
+    // hand in `UserPatchRequest::validate_flat` below.
+    #[serde(default, skip_serializing_if = "Undefinable::is_missing")]
+    #[schema(example = "Engineering")]
+    pub department: Undefinable<String>,
+}
+
+/// Request body for partially updating an existing user (PATCH).
+#[derive(Debug, Clone, Deserialize, ToSchema, Validate)]
+pub struct UserPatchRequest {
+    #[serde(flatten)]
+    #[validate(nested)]
+    pub data: UserPatchRequestData,
+}
+
+impl UserPatchRequest {
+    /// See `UserCreateRequest::validate_flat`. Unlike that one, also checks
+    /// `data.department`'s length by hand afterwards, since `Undefinable`
+    /// can't be covered by the `#[validate(nested)]` derive above.
+    pub fn validate_flat(&self) -> Vec<(&'static str, String)> {
+        let mut flat = match self.validate() {
+            Ok(()) => Vec::new(),
+            Err(errors) => flatten_validation_errors(&errors),
+        };
+        if let Undefinable::Value(department) = &self.data.department {
+            if department.len() > 128 {
+                flat.push(("department", "must be at most 128 characters".to_string()));
+            }
+        }
+        flat
+    }
+}
+
+impl UserPatchRequestData {
+    /// Applies only the fields the caller actually provided onto `user`,
+    /// leaving everything else untouched. `department` follows `Undefinable`
+    /// semantics: `Missing` leaves it alone, `Null` clears it, `Value` sets it.
+    pub fn apply_to(self, user: &mut User) {
+        if let Some(user_name) = self.user_name {
+            user.user_name = user_name;
+        }
+        if let Some(first_name) = self.first_name {
+            user.first_name = first_name;
+        }
+        if let Some(last_name) = self.last_name {
+            user.last_name = last_name;
+        }
+        if let Some(email) = self.email {
+            user.email = email;
+        }
+        if let Some(user_status) = self.user_status {
+            user.user_status = user_status;
+        }
+        match self.department {
+            Undefinable::Missing => {}
+            Undefinable::Null => user.department = None,
+            Undefinable::Value(value) => user.department = Some(value),
+        }
+    }
+}
+
+// --- User List Query / Paginated Response ---
+
+/// Maximum rows returned per page, regardless of what the caller requests.
+pub const MAX_PER_PAGE: u32 = 100;
+const DEFAULT_PAGE: u32 = 1;
+const DEFAULT_PER_PAGE: u32 = 20;
+
+/// Query parameters accepted by `GET /users`. `sort` is a whitelisted column
+/// name optionally prefixed with `-` for descending order (e.g. `-createdAt`);
+/// unrecognized values fall back to the repository's default ordering.
+/// `q` does a case-insensitive substring match across user_name, first_name,
+/// last_name, and email.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserListQuery {
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+    pub sort: Option<String>,
+    pub q: Option<String>,
+}
+
+impl UserListQuery {
+    /// Page number, clamped to at least 1.
+    pub fn page(&self) -> u32 {
+        self.page.unwrap_or(DEFAULT_PAGE).max(1)
+    }
+
+    /// Rows per page, clamped to `[1, MAX_PER_PAGE]`.
+    pub fn per_page(&self) -> u32 {
+        self.per_page
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE)
+    }
+}
+
+/// Generic pagination envelope returned by collection endpoints.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PagedResponse<T> {
+    pub data: Vec<T>,
+    pub page: u32,
+    pub per_page: u32,
+    pub total: i64,
+}
+
+// --- Auth Request/Response Models ---
+
+/// Request body for `POST /auth/login`.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginRequest {
+    #[schema(example = "jdoe")]
+    pub user_name: String,
+    #[schema(example = "hunter2")]
+    pub password: String,
+}
+
+/// Response body for a successful login.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginResponse {
+    pub token: String,
+}
+
+// --- Healthcheck Response Models ---
+
+/// Response for `GET /health/live`: reports only that the process is up and
+/// answering requests. Never touches the database, so it stays cheap and
+/// stays `200 OK` even while a dependency is down — that's what
+/// `/health/ready` is for.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LivenessStatus {
+    /// Always "OK"; the handler responding at all is the signal.
+    #[schema(example = "OK")]
+    status: String,
+    /// Server uptime duration (e.g., "1h 15m 30s").
+    #[schema(example = "1h 15m 30s")]
+    uptime: String,
+}
+
+impl LivenessStatus {
+    pub fn new(uptime_duration: Option<time::Duration>) -> Self {
+        Self {
+            status: "OK".to_string(),
+            uptime: format_uptime(uptime_duration),
+        }
+    }
+}
+
+/// Response for `GET /health/ready`: reports whether the service's
+/// dependencies (currently just the database) are usable, so orchestrators
+/// can gate traffic on it. Returns `503` via the handler when `db_status`
+/// is `"FAIL"`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct HealthStatus {
     /// Status of the database connection ("OK" or "FAIL").
+    #[schema(example = "OK")]
     db_status: String,
+    /// Round-trip time of the readiness DB probe, in milliseconds. `None` if
+    /// the probe failed (e.g. the pool itself failed to hand out a
+    /// connection).
+    #[schema(example = 4)]
+    db_latency_ms: Option<u64>,
+    /// Current number of connections held by the pool (in use + idle).
+    #[schema(example = 5)]
+    pool_size: u32,
+    /// Number of pool connections currently idle.
+    #[schema(example = 4)]
+    pool_idle: usize,
     /// Server uptime duration (e.g., "1h 15m 30s").
+    #[schema(example = "1h 15m 30s")]
     uptime: String,
-    // Add mem_usage back if you implement it using sysinfo or similar
-    //
-    // mem_usage: String,
+    /// Resident memory used by the current process, human-readable (e.g. "128.4 MiB").
+    #[schema(example = "128.4 MiB")]
+    mem_usage: String,
+    /// CPU usage of the current process, as a formatted percentage (e.g. "2.3%").
+    #[schema(example = "2.3%")]
+    cpu_usage: String,
 }
 
 impl HealthStatus {
-    /// Creates a new HealthStatus instance.
-    pub fn new(db_ok: bool, uptime_duration: Option<time::Duration>) -> Self {
+    /// Creates a new HealthStatus instance from already-formatted `mem_usage`/`cpu_usage`.
+    /// Most callers want `HealthStatus::collect`, which samples these from `sysinfo` itself.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        db_ok: bool,
+        db_latency_ms: Option<u64>,
+        pool_size: u32,
+        pool_idle: usize,
+        uptime_duration: Option<time::Duration>,
+        mem_usage: impl Into<String>,
+        cpu_usage: impl Into<String>,
+    ) -> Self {
         let db_status = if db_ok { "OK" } else { "FAIL" }.to_string();
-
-        let uptime_str = uptime_duration.map_or_else(
-            || "N/A".to_string(),
-            |duration| {
-                // Format duration into a human-readable string
-                let secs = duration.whole_seconds();
-                let hours = secs / 3600;
-                let mins = (secs % 3600) / 60;
-                let secs = secs % 60;
-
-                let mut parts = Vec::new();
-                if hours > 0 {
-                    parts.push(format!("{}h", hours));
-                }
-                if mins > 0 {
-                    parts.push(format!("{}m", mins));
-                }
-                // Always include seconds unless duration is exactly 0
-                if secs > 0 || parts.is_empty() {
-                    parts.push(format!("{}s", secs));
-                }
-
-                parts.join(" ")
-            },
-        );
-
         HealthStatus {
             db_status,
-            uptime: uptime_str,
-            // mem_usage: "N/A".to_string(), // Placeholder if not implemented
+            db_latency_ms,
+            pool_size,
+            pool_idle,
+            uptime: format_uptime(uptime_duration),
+            mem_usage: mem_usage.into(),
+            cpu_usage: cpu_usage.into(),
         }
     }
+
+    /// Creates a new HealthStatus, sampling the current process's memory and
+    /// CPU usage from `sys`. `sys` should already have been refreshed for
+    /// `pid` (see `api::health::HealthMonitor`) immediately before this call.
+    #[allow(clippy::too_many_arguments)]
+    pub fn collect(
+        db_ok: bool,
+        db_latency_ms: Option<u64>,
+        pool_size: u32,
+        pool_idle: usize,
+        uptime_duration: Option<time::Duration>,
+        sys: &sysinfo::System,
+        pid: sysinfo::Pid,
+    ) -> Self {
+        let (mem_usage, cpu_usage) = match sys.process(pid) {
+            Some(process) => (format_bytes(process.memory()), format!("{:.1}%", process.cpu_usage())),
+            None => ("N/A".to_string(), "N/A".to_string()),
+        };
+        Self::new(db_ok, db_latency_ms, pool_size, pool_idle, uptime_duration, mem_usage, cpu_usage)
+    }
+
+    /// Whether the readiness response should be reported as healthy.
+    pub fn is_ready(&self) -> bool {
+        self.db_status == "OK"
+    }
+}
+
+/// Formats a duration into a human-readable string, e.g. "1h 1m 5s".
+fn format_uptime(uptime_duration: Option<time::Duration>) -> String {
+    uptime_duration.map_or_else(
+        || "N/A".to_string(),
+        |duration| {
+            let secs = duration.whole_seconds();
+            let hours = secs / 3600;
+            let mins = (secs % 3600) / 60;
+            let secs = secs % 60;
+
+            let mut parts = Vec::new();
+            if hours > 0 {
+                parts.push(format!("{}h", hours));
+            }
+            if mins > 0 {
+                parts.push(format!("{}m", mins));
+            }
+            // Always include seconds unless duration is exactly 0
+            if secs > 0 || parts.is_empty() {
+                parts.push(format!("{}s", secs));
+            }
+
+            parts.join(" ")
+        },
+    )
+}
+
+/// Formats a byte count as a human-readable string with binary (1024-based)
+/// units, e.g. `134637158` -> "128.4 MiB".
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
 }
 
 // --- Unit Tests ---
@@ -140,33 +802,51 @@ impl HealthStatus {
 mod tests {
     use super::*;
 
-    // --- Tests (Assuming String representation) ---
+    // --- UserStatus Tests ---
     #[test]
-    fn test_user_status_serde_as_string() {
-        // Test serialization
-        let active_status = String::from("A");
-        let json = serde_json::to_string(&active_status).unwrap();
-        assert_eq!(json, "\"A\""); // String serializes with quotes
+    fn test_user_status_serde_round_trip() {
+        assert_eq!(serde_json::to_string(&UserStatus::Active).unwrap(), "\"A\"");
+        assert_eq!(serde_json::to_string(&UserStatus::Inactive).unwrap(), "\"I\"");
+        assert_eq!(serde_json::to_string(&UserStatus::Terminated).unwrap(), "\"T\"");
 
-        // Test deserialization
-        let deserialized: String = serde_json::from_str("\"A\"").unwrap();
-        assert_eq!(deserialized, "A");
+        assert_eq!(
+            serde_json::from_str::<UserStatus>("\"A\"").unwrap(),
+            UserStatus::Active
+        );
+        assert_eq!(
+            serde_json::from_str::<UserStatus>("\"I\"").unwrap(),
+            UserStatus::Inactive
+        );
+        assert_eq!(
+            serde_json::from_str::<UserStatus>("\"T\"").unwrap(),
+            UserStatus::Terminated
+        );
+    }
 
-        let inactive_json = "\"I\"";
-        let deserialized_i: String = serde_json::from_str(inactive_json).unwrap();
-        assert_eq!(deserialized_i, "I");
+    #[test]
+    fn test_user_status_rejects_unknown_code() {
+        let result = serde_json::from_str::<UserStatus>("\"X\"");
+        assert!(result.is_err(), "unknown status code should fail to deserialize");
+    }
 
-        let terminated_json = "\"T\"";
-        let deserialized_t: String = serde_json::from_str(terminated_json).unwrap();
-        assert_eq!(deserialized_t, "T");
+    // --- Validation Tests ---
 
-        // Any string will deserialize, so no "invalid" test in this context
-        let other_json = "\"X\"";
-        let deserialized_x: String = serde_json::from_str(other_json).unwrap();
-        assert_eq!(deserialized_x, "X");
+    /// Asserts `errors` has at least one error on `field` with code `code`.
+    fn assert_err_code(errors: &validator::ValidationErrors, field: &'static str, code: &str) {
+        let field_errors = errors
+            .field_errors()
+            .get(field)
+            .unwrap_or_else(|| panic!("expected a validation error on field '{}'", field));
+        assert!(
+            field_errors.iter().any(|e| e.code == code),
+            "expected field '{}' to have error code '{}', got {:?}",
+            field,
+            code,
+            field_errors
+        );
     }
 
-    // --- UserCreateRequest Tests (Validation removed) ---
+    // --- UserCreateRequest Tests ---
     #[test]
     fn test_create_request_struct_creation() {
         // Test that the struct can be created
@@ -176,15 +856,15 @@ mod tests {
                 first_name: Some("Valid".to_string()),
                 last_name: Some("User".to_string()),
                 email: Some("valid@example.com".to_string()),
-                user_status: Some(String::from("A")), // Use String
+                user_status: Some(UserStatus::Active),
                 department: Some("Test Dept".to_string()),
+                password: Some("hunter22".to_string()),
             },
         };
         // Simple assertion to ensure it was created
         assert_eq!(req.data.user_name.unwrap(), "validuser");
     }
 
-    /* // Validation tests commented out as per prompt
     #[test]
     fn test_create_request_valid() {
         let req = UserCreateRequest {
@@ -193,12 +873,13 @@ mod tests {
                 first_name: Some("Valid".to_string()),
                 last_name: Some("User".to_string()),
                 email: Some("valid@example.com".to_string()),
-                user_status: Some(String::from("A")), // Use String
+                user_status: Some(UserStatus::Active),
                 department: Some("Test Dept".to_string()),
+                password: Some("hunter22".to_string()),
             },
         };
-        // Validate the outer request struct, which triggers validation on inner 'data' field
-        // assert!(req.validate().is_ok()); // REMOVED
+        assert!(req.validate().is_ok());
+        assert!(req.validate_flat().is_empty());
     }
 
     #[test]
@@ -209,13 +890,14 @@ mod tests {
                 first_name: Some("Valid".to_string()),
                 last_name: Some("User".to_string()),
                 email: Some("valid@example.com".to_string()),
-                user_status: Some(String::from("A")), // Use String
+                user_status: Some(UserStatus::Active),
                 department: None,
+                password: Some("hunter22".to_string()),
             },
         };
-        // let errors = req.validate().unwrap_err(); // REMOVED
-        // Pass the errors object directly to the helper
-        // validate_err_codes(&errors, &["user_name"], &["required"]); // REMOVED
+        let errors = req.validate().unwrap_err();
+        assert_err_code(&errors, "user_name", "required");
+        assert!(req.validate_flat().iter().any(|(f, _)| *f == "user_name"));
     }
 
     #[test]
@@ -226,39 +908,35 @@ mod tests {
                 first_name: Some("Valid".to_string()),
                 last_name: Some("User".to_string()),
                 email: Some("invalid-email".to_string()), // Invalid email
-                user_status: Some(String::from("A")), // Use String
+                user_status: Some(UserStatus::Active),
                 department: None,
+                password: Some("hunter22".to_string()),
             },
         };
-        // let errors = req.validate().unwrap_err(); // REMOVED
-        // Check that both username (regex) and email (email format) errors occurred
-        // assert!(errors.field_errors().contains_key("user_name")); // REMOVED
-        // assert!(errors.field_errors().contains_key("email")); // REMOVED
-        // validate_err_codes(&errors, &["user_name"], &["regex"]); // REMOVED
-        // validate_err_codes(&errors, &["email"], &["email"]); // REMOVED
+        let errors = req.validate().unwrap_err();
+        assert_err_code(&errors, "user_name", "regex");
+        assert_err_code(&errors, "email", "email");
     }
 
     #[test]
     fn test_create_request_length_errors() {
         let req = UserCreateRequest {
             data: UserCreateRequestData {
-                user_name: Some("abc".to_string()), // Too short
+                user_name: Some("abc".to_string()), // Too short; caught by the username regex
                 first_name: Some("".to_string()),   // Too short (violates min=1)
                 last_name: Some("User".to_string()),
                 email: Some("valid@example.com".to_string()),
-                user_status: Some(String::from("A")), // Use String
+                user_status: Some(UserStatus::Active),
                 department: None,
+                password: Some("hunter22".to_string()),
             },
         };
-        // let errors = req.validate().unwrap_err(); // REMOVED
-        // assert!(errors.field_errors().contains_key("user_name")); // REMOVED
-        // assert!(errors.field_errors().contains_key("first_name")); // REMOVED
-        // validate_err_codes(&errors, &["user_name"], &["length"]); // REMOVED
-        // validate_err_codes(&errors, &["first_name"], &["length"]); // REMOVED
+        let errors = req.validate().unwrap_err();
+        assert_err_code(&errors, "user_name", "regex");
+        assert_err_code(&errors, "first_name", "length");
     }
-    */
 
-    // --- UserUpdateRequest Tests (Validation removed) ---
+    // --- UserUpdateRequest Tests ---
     #[test]
     fn test_update_request_struct_creation() {
         let req = UserUpdateRequest {
@@ -267,14 +945,14 @@ mod tests {
                 first_name: Some("Valid".to_string()),
                 last_name: Some("User".to_string()),
                 email: Some("valid@example.com".to_string()),
-                user_status: Some(String::from("I")), // Use String
-                department: None,                     // Optional is fine
+                user_status: Some(UserStatus::Inactive),
+                department: None, // Optional is fine
+                password: None,
             },
         };
-        assert_eq!(req.data.user_status.unwrap(), "I");
+        assert_eq!(req.data.user_status.unwrap(), UserStatus::Inactive);
     }
 
-    /* // Validation tests commented out as per prompt
     #[test]
     fn test_update_request_valid() {
         let req = UserUpdateRequest {
@@ -283,11 +961,12 @@ mod tests {
                 first_name: Some("Valid".to_string()),
                 last_name: Some("User".to_string()),
                 email: Some("valid@example.com".to_string()),
-                user_status: Some(String::from("I")), // Use String
+                user_status: Some(UserStatus::Inactive),
                 department: None, // Optional is fine
+                password: None,
             },
         };
-        // assert!(req.validate().is_ok()); // REMOVED
+        assert!(req.validate().is_ok());
     }
 
     #[test]
@@ -298,41 +977,183 @@ mod tests {
                 first_name: None, // Missing first name
                 last_name: Some("User".to_string()),
                 email: Some("valid@example.com".to_string()),
-                user_status: Some(String::from("I")), // Use String
+                user_status: Some(UserStatus::Inactive),
                 department: None,
+                password: None,
             },
         };
-        // let errors = req.validate().unwrap_err(); // REMOVED
-        // validate_err_codes(&errors, &["first_name"], &["required"]); // REMOVED
+        let errors = req.validate().unwrap_err();
+        assert_err_code(&errors, "first_name", "required");
+        assert!(req.validate_flat().iter().any(|(f, _)| *f == "first_name"));
+    }
+
+    // --- Undefinable / Patch Tests ---
+
+    /// Builds a fully-populated `User` for patch-folding tests.
+    fn make_test_user() -> User {
+        User {
+            id: 1,
+            user_name: "jdoe".to_string(),
+            first_name: "Jane".to_string(),
+            last_name: "Doe".to_string(),
+            email: "jane.doe@example.com".to_string(),
+            user_status: UserStatus::Active,
+            department: None,
+            role: Role::User,
+            created_at: OffsetDateTime::now_utc(),
+            updated_at: OffsetDateTime::now_utc(),
+            permissions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_undefinable_missing_key_deserializes_to_missing() {
+        let data: UserPatchRequestData = serde_json::from_str(r#"{}"#).unwrap();
+        assert_eq!(data.department, Undefinable::Missing);
+    }
+
+    #[test]
+    fn test_undefinable_explicit_null_deserializes_to_null() {
+        let data: UserPatchRequestData = serde_json::from_str(r#"{"department": null}"#).unwrap();
+        assert_eq!(data.department, Undefinable::Null);
+    }
+
+    #[test]
+    fn test_undefinable_value_deserializes_to_value() {
+        let data: UserPatchRequestData = serde_json::from_str(r#"{"department": "Sales"}"#).unwrap();
+        assert_eq!(data.department, Undefinable::Value("Sales".to_string()));
+    }
+
+    #[test]
+    fn test_patch_apply_to_leaves_missing_fields_untouched() {
+        let mut user = make_test_user();
+        let original_department = user.department.clone();
+        let patch = UserPatchRequestData {
+            user_name: None,
+            first_name: Some("Patched".to_string()),
+            last_name: None,
+            email: None,
+            user_status: None,
+            department: Undefinable::Missing,
+        };
+        patch.apply_to(&mut user);
+        assert_eq!(user.first_name, "Patched");
+        assert_eq!(user.department, original_department);
+    }
+
+    #[test]
+    fn test_patch_apply_to_null_clears_department() {
+        let mut user = make_test_user();
+        user.department = Some("Engineering".to_string());
+        let patch = UserPatchRequestData {
+            user_name: None,
+            first_name: None,
+            last_name: None,
+            email: None,
+            user_status: None,
+            department: Undefinable::Null,
+        };
+        patch.apply_to(&mut user);
+        assert_eq!(user.department, None);
+    }
+
+    #[test]
+    fn test_patch_apply_to_value_sets_department() {
+        let mut user = make_test_user();
+        user.department = None;
+        let patch = UserPatchRequestData {
+            user_name: None,
+            first_name: None,
+            last_name: None,
+            email: None,
+            user_status: None,
+            department: Undefinable::Value("Finance".to_string()),
+        };
+        patch.apply_to(&mut user);
+        assert_eq!(user.department, Some("Finance".to_string()));
+    }
+
+    #[test]
+    fn test_patch_validate_flat_empty_body_is_valid() {
+        let req: UserPatchRequest = serde_json::from_str(r#"{}"#).unwrap();
+        assert!(req.validate_flat().is_empty());
+    }
+
+    #[test]
+    fn test_patch_validate_flat_rejects_invalid_email() {
+        let req: UserPatchRequest = serde_json::from_str(r#"{"email": "not-an-email"}"#).unwrap();
+        assert!(req.validate_flat().iter().any(|(f, _)| *f == "email"));
+    }
+
+    #[test]
+    fn test_patch_validate_flat_rejects_oversized_department() {
+        let body = serde_json::json!({ "department": "x".repeat(129) });
+        let req: UserPatchRequest = serde_json::from_value(body).unwrap();
+        assert!(req.validate_flat().iter().any(|(f, _)| *f == "department"));
     }
-    */
 
     // --- HealthStatus Tests ---
     #[test]
     fn test_health_status_ok_formatting() {
-        let status = HealthStatus::new(true, Some(time::Duration::seconds(90))); // 1m 30s
+        let status = HealthStatus::new(true, Some(4), 5, 4, Some(time::Duration::seconds(90)), "10.0 MiB", "1.0%"); // 1m 30s
         assert_eq!(status.db_status, "OK");
+        assert_eq!(status.db_latency_ms, Some(4));
+        assert_eq!(status.pool_size, 5);
+        assert_eq!(status.pool_idle, 4);
         assert_eq!(status.uptime, "1m 30s");
+        assert_eq!(status.mem_usage, "10.0 MiB");
+        assert_eq!(status.cpu_usage, "1.0%");
+        assert!(status.is_ready());
     }
 
     #[test]
     fn test_health_status_fail_formatting() {
-        let status = HealthStatus::new(false, Some(time::Duration::seconds(3665))); // 1h 1m 5s
+        let status = HealthStatus::new(false, None, 5, 5, Some(time::Duration::seconds(3665)), "10.0 MiB", "1.0%"); // 1h 1m 5s
         assert_eq!(status.db_status, "FAIL");
+        assert_eq!(status.db_latency_ms, None);
         assert_eq!(status.uptime, "1h 1m 5s");
+        assert!(!status.is_ready());
     }
 
     #[test]
     fn test_health_status_no_uptime() {
-        let status = HealthStatus::new(true, None);
+        let status = HealthStatus::new(true, Some(1), 5, 4, None, "10.0 MiB", "1.0%");
         assert_eq!(status.db_status, "OK");
         assert_eq!(status.uptime, "N/A");
     }
 
     #[test]
     fn test_health_status_zero_uptime() {
-        let status = HealthStatus::new(true, Some(time::Duration::ZERO));
+        let status = HealthStatus::new(true, Some(1), 5, 4, Some(time::Duration::ZERO), "10.0 MiB", "1.0%");
         assert_eq!(status.db_status, "OK");
         assert_eq!(status.uptime, "0s"); // Should show 0s
     }
+
+    #[test]
+    fn test_liveness_status_formatting() {
+        let status = LivenessStatus::new(Some(time::Duration::seconds(90)));
+        assert_eq!(status.status, "OK");
+        assert_eq!(status.uptime, "1m 30s");
+    }
+
+    // --- Byte Formatting Tests ---
+    #[test]
+    fn test_format_bytes_sub_kib() {
+        assert_eq!(format_bytes(512), "512 B");
+    }
+
+    #[test]
+    fn test_format_bytes_kib() {
+        assert_eq!(format_bytes(2048), "2.0 KiB");
+    }
+
+    #[test]
+    fn test_format_bytes_mib() {
+        assert_eq!(format_bytes(128 * 1024 * 1024 + 419_430), "128.4 MiB");
+    }
+
+    #[test]
+    fn test_format_bytes_gib() {
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
 }
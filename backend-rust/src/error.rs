@@ -1,9 +1,116 @@
-use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use actix_web::{http::StatusCode, HttpRequest, HttpResponse, Responder, ResponseError};
 use serde::{Deserialize, Serialize};
 // Added Deserialize
 use sqlx::Error as SqlxError;
+use std::cell::RefCell;
 use std::fmt::{Debug, Formatter};
-use validator::ValidationErrors;
+
+// --- Call-site Trace Chain ---
+//
+// Server-side-only breadcrumbs for 5xx diagnostics: as an `AppResult`
+// propagates up through `trace_err!`-annotated `?` points in the
+// service/repository layers, each hop's call site is appended here. None of
+// this is ever serialized into `ApiResponse` — see `error_response` below,
+// which logs the chain but never puts it in the client-facing body.
+//
+// Scoped per request via a `tokio::task_local!`, populated by
+// `middleware::trace_chain::TraceChain` (wrapped around the whole request
+// future in `main.rs`). `trace_err!` degrades to a no-op if called outside
+// that scope (e.g. in a unit test that calls a service function directly),
+// so it's always safe to use.
+
+/// One hop in an error's propagation path.
+#[derive(Debug, Clone, Copy)]
+pub struct Trace {
+    pub file: &'static str,
+    pub line: u32,
+    pub column: u32,
+    pub function: &'static str,
+}
+
+/// The accumulated propagation path for the error currently being handled,
+/// innermost hop first.
+#[derive(Debug, Clone, Default)]
+pub struct Traces(pub Vec<Trace>);
+
+tokio::task_local! {
+    static TRACE_BUFFER: RefCell<Vec<Trace>>;
+}
+
+/// Runs `fut` with a fresh, empty trace buffer scoped to it — one call per
+/// request, made by `middleware::trace_chain::TraceChain`.
+pub async fn with_trace_scope<F: std::future::Future>(fut: F) -> F::Output {
+    TRACE_BUFFER.scope(RefCell::new(Vec::new()), fut).await
+}
+
+/// Appends `trace` to the current request's trace buffer. A no-op outside
+/// `with_trace_scope` (there is no buffer to append to).
+#[doc(hidden)]
+pub fn push_trace(trace: Trace) {
+    let _ = TRACE_BUFFER.try_with(|buf| buf.borrow_mut().push(trace));
+}
+
+/// Snapshots the current request's trace buffer, innermost hop first.
+/// Called once, from `error_response`, to log the full chain.
+fn current_traces() -> Traces {
+    Traces(TRACE_BUFFER.try_with(|buf| buf.borrow().clone()).unwrap_or_default())
+}
+
+/// Resolves the name of the function this macro is expanded in, via the
+/// usual dependency-free trick (a local fn's `type_name` includes the
+/// enclosing path, minus the trailing `::f`).
+#[macro_export]
+macro_rules! function_name {
+    () => {{
+        fn f() {}
+        fn type_name_of<T>(_: T) -> &'static str {
+            std::any::type_name::<T>()
+        }
+        let name = type_name_of(f);
+        &name[..name.len() - 3]
+    }};
+}
+
+/// Records the current call site onto the request's trace chain when
+/// `$result` is an `Err`, then yields `$result` back unchanged so it
+/// composes with `?` exactly like the expression it wraps. `file!()`/
+/// `line!()`/`column!()` are expanded at the call site, which is the whole
+/// reason this is a macro and not a plain `.trace()` method.
+#[macro_export]
+macro_rules! trace_err {
+    ($result:expr) => {
+        match $result {
+            Ok(value) => Ok(value),
+            Err(err) => {
+                $crate::error::push_trace($crate::error::Trace {
+                    file: file!(),
+                    line: line!(),
+                    column: column!(),
+                    function: $crate::function_name!(),
+                });
+                Err(err)
+            }
+        }
+    };
+}
+
+// --- Field-level Validation Errors ---
+
+/// A single field-level validation failure, carried by `AppError::Validation`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
+        }
+    }
+}
 
 // --- Custom Error Enum ---
 
@@ -14,21 +121,44 @@ pub enum AppError {
     #[error("Database error")] // Base message, details in source
     Database(#[from] SqlxError),
 
-    // Validation variant removed
+    /// One or more request fields failed validation.
     #[error("Validation error(s)")]
-    Validation(#[from] ValidationErrors),
+    Validation(Vec<FieldError>),
 
     /// Configuration loading or parsing errors.
     #[error("Configuration error")]
     Config(#[from] config::ConfigError),
 
+    /// Applying embedded schema migrations failed.
+    #[error("Migration error: {0}")]
+    Migration(String),
+
     /// Resource not found (e.g., user ID doesn't exist).
-    #[error("Not found: {0}")]
-    NotFound(String),
+    #[error("Not found: {resource} '{id}'")]
+    NotFound { resource: &'static str, id: String },
 
     /// Conflict error (e.g., unique constraint violation like username/email exists).
-    #[error("Conflict: {0}")]
-    Conflict(String),
+    #[error("Conflict: {field} '{value}' already exists")]
+    Conflict { field: String, value: String },
+
+    /// A write referenced a row that doesn't exist (Postgres foreign-key
+    /// violation, SQLSTATE `23503`), e.g. setting a credential for a user
+    /// ID that was deleted between the caller's lookup and this write.
+    #[error("Referenced resource does not exist: {constraint} on '{field}'")]
+    ReferencedResourceMissing { field: String, constraint: String },
+
+    /// A write violated a Postgres NOT NULL (`23502`) or CHECK (`23514`)
+    /// constraint that wasn't already caught by request validation.
+    #[error("Constraint violation: {constraint} on '{field}'")]
+    ConstraintViolation { field: String, constraint: String },
+
+    /// Authentication failed or is missing (e.g. bad credentials, invalid/expired token).
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    /// Caller is authenticated but lacks the role/permission required for the action.
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
 
     /// Invalid request data or format (not covered by validation, e.g., bad JSON).
     #[allow(dead_code)]
@@ -57,36 +187,112 @@ impl Debug for AppError {
         match self {
             AppError::Database(err) => write!(f, "AppError::Database({:?})", err),
             AppError::Config(err) => write!(f, "AppError::Config({:?})", err),
-            AppError::NotFound(msg) => write!(f, "AppError::NotFound({})", msg),
-            AppError::Conflict(msg) => write!(f, "AppError::Conflict({})", msg),
+            AppError::Migration(msg) => write!(f, "AppError::Migration({})", msg),
+            AppError::NotFound { resource, id } => {
+                write!(f, "AppError::NotFound({}, {})", resource, id)
+            }
+            AppError::Conflict { field, value } => {
+                write!(f, "AppError::Conflict({}, {})", field, value)
+            }
+            AppError::ReferencedResourceMissing { field, constraint } => {
+                write!(f, "AppError::ReferencedResourceMissing({}, {})", field, constraint)
+            }
+            AppError::ConstraintViolation { field, constraint } => {
+                write!(f, "AppError::ConstraintViolation({}, {})", field, constraint)
+            }
+            AppError::Unauthorized(msg) => write!(f, "AppError::Unauthorized({})", msg),
+            AppError::Forbidden(msg) => write!(f, "AppError::Forbidden({})", msg),
             AppError::BadRequest(msg) => write!(f, "AppError::BadRequest({})", msg),
             AppError::Internal(msg) => write!(f, "AppError::Internal({})", msg),
             AppError::Io(err) => write!(f, "AppError::Io({:?})", err),
             AppError::Anyhow(err) => write!(f, "AppError::Anyhow({:?})", err),
-            AppError::Validation(err) => write!(f, "AppError::Validation({:?})", err),
+            AppError::Validation(errors) => write!(f, "AppError::Validation({:?})", errors),
         }
     }
 }
 
 // --- Actix ResponseError Implementation ---
 
-/// Structure for serializing/deserializing errors into a JSON response body.
-#[derive(Serialize, Deserialize, Debug, PartialEq)] // Added Deserialize, Debug, PartialEq
-pub struct ErrorResponse {
-    // Make fields public for direct comparison in tests or add getter methods
-    pub status: u16,
-    pub error: String, // Use String to own the data after deserialization
+/// Uniform JSON envelope for the auth and users handlers' responses and for
+/// every `AppError`, success or failure, so clients branch on `success` rather
+/// than on shape: `{ "success": true, "data": ... }` or
+/// `{ "success": false, "error": { code, message, ... } }`. `data`/`error`
+/// are mutually exclusive and each omitted (not `null`) on the side that
+/// doesn't apply. The health endpoints' bodies, the rate limiter's empty
+/// 429 and the default-service 404 are not wrapped in it.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ApiResponse<T> {
+    pub success: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub message: Option<String>,
+    pub data: Option<T>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub details: Option<serde_json::Value>,
+    pub error: Option<ErrorBody>,
+}
+
+impl<T> ApiResponse<T> {
+    /// Builds the `{ success: true, data }` form.
+    pub fn success(data: T) -> Self {
+        Self {
+            success: true,
+            data: Some(data),
+            error: None,
+        }
+    }
+
+    /// Builds the `{ success: false, error }` form.
+    pub fn failure(error: ErrorBody) -> Self {
+        Self {
+            success: false,
+            data: None,
+            error: Some(error),
+        }
+    }
+}
+
+impl<T> From<AppError> for ApiResponse<T> {
+    /// Lets a handler's `AppError` be wrapped into the same envelope its
+    /// success path uses, via `?` + `.map_err(Into::into)` if it isn't
+    /// already flowing through `ResponseError::error_response` below.
+    fn from(err: AppError) -> Self {
+        Self::failure(err.to_response_body())
+    }
+}
+
+impl<T: Serialize> Responder for ApiResponse<T> {
+    type Body = actix_web::body::BoxBody;
+
+    /// Lets a handler return `AppResult<ApiResponse<T>>` (or just
+    /// `ApiResponse<T>`) and skip the `HttpResponse::Ok().json(...)`
+    /// boilerplate for the common 200-OK-with-envelope case. Handlers that
+    /// need a different success status (e.g. 201 Created, 204 No Content)
+    /// still build their own `HttpResponse` explicitly.
+    fn respond_to(self, _req: &HttpRequest) -> HttpResponse<Self::Body> {
+        HttpResponse::Ok().json(self)
+    }
 }
 
-// Helper struct to satisfy lifetimes for ErrorResponse owned String
-struct ErrorResponseOwnedData {
-    error_category: &'static str,
-    message: Option<String>,
-    details: Option<serde_json::Value>,
+/// The structured payload nested under a failure `ApiResponse::error`.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ErrorBody {
+    /// Stable, machine-readable error code (e.g. "USER_NOT_FOUND",
+    /// "DUPLICATE_EMAIL"). Contract-stable: clients should branch on this,
+    /// never on `message`, which is free-form English and may change.
+    pub code: String,
+    /// Namespaced i18n lookup key (e.g. "errors.user.conflict.email") so
+    /// front-ends can localize without parsing `message`.
+    pub message_key: String,
+    /// Human-readable message, safe to display to API consumers. Serves as
+    /// the default/fallback text for clients that haven't localized `code`.
+    pub message: String,
+    /// Field-level detail, populated only for validation failures.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<FieldError>>,
+    /// Offending table/constraint, populated only for foreign-key, NOT NULL
+    /// and CHECK violations (`{ "field": ..., "constraint": ... }`, where
+    /// `field` holds the table name). Unique-violation `Conflict` bodies
+    /// carry no `details`; their field is named in `message`/`message_key`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
 }
 
 impl ResponseError for AppError {
@@ -94,20 +300,22 @@ impl ResponseError for AppError {
     fn status_code(&self) -> StatusCode {
         match self {
             // 4xx Client Errors
-            AppError::NotFound(_) => StatusCode::NOT_FOUND, // 404
-            AppError::Conflict(_) => StatusCode::CONFLICT,  // 409
+            AppError::NotFound { .. } => StatusCode::NOT_FOUND, // 404
+            AppError::Conflict { .. } => StatusCode::CONFLICT,  // 409
+            AppError::ReferencedResourceMissing { .. } => StatusCode::CONFLICT, // 409
+            AppError::ConstraintViolation { .. } => StatusCode::BAD_REQUEST, // 400
             AppError::BadRequest(_) => StatusCode::BAD_REQUEST, // 400
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED, // 401
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,     // 403
+            AppError::Validation(_) => StatusCode::BAD_REQUEST, // 400
 
             // Specific DB errors mapping to client errors
             AppError::Database(SqlxError::RowNotFound) => StatusCode::NOT_FOUND, // 404
-            AppError::Database(err) if is_unique_constraint_violation(err) => StatusCode::CONFLICT, // 409
-
-            // Validation errors mapping to client errors
-            AppError::Validation(_) => StatusCode::BAD_REQUEST,
 
             // 5xx Server Errors
             AppError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR, // 500 (Default for DB errors)
             AppError::Config(_) => StatusCode::INTERNAL_SERVER_ERROR,   // 500
+            AppError::Migration(_) => StatusCode::INTERNAL_SERVER_ERROR, // 500
             AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR, // 500
             AppError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,       // 500
             AppError::Anyhow(_) => StatusCode::INTERNAL_SERVER_ERROR,   // 500
@@ -117,95 +325,140 @@ impl ResponseError for AppError {
     /// Creates the HTTP response body for the error.
     fn error_response(&self) -> HttpResponse {
         let status = self.status_code();
-        let ErrorResponseOwnedData {
-            error_category,
-            message,
-            details,
-        } = self.get_response_details();
+        let body = self.to_response_body();
 
         // Log the full error details internally before sending response
         if status.is_server_error() {
-            tracing::error!(error.status = %status, error.category = error_category, error.message = ?message, error.details = ?details, error.source = ?self, "API Error Response (5xx)");
+            // The trace chain is diagnostic-only breadcrumbs for this log
+            // line; it never appears in `body`/`ApiResponse`.
+            let traces = current_traces();
+            tracing::error!(error.status = %status, error.code = body.code, error.message = body.message, error.source = ?self, error.traces = ?traces.0, "API Error Response (5xx)");
         } else {
             // Use warn for 4xx, but info might also be appropriate depending on the error type
-            tracing::warn!(error.status = %status, error.category = error_category, error.message = ?message, error.details = ?details, error.source = ?self, "API Error Response (4xx)");
+            tracing::warn!(error.status = %status, error.code = body.code, error.message = body.message, error.source = ?self, "API Error Response (4xx)");
         }
 
-        HttpResponse::build(status).json(ErrorResponse {
-            status: status.as_u16(),
-            error: error_category.to_string(), // Convert &'static str to String
-            message,
-            details,
-        })
+        let mut response = HttpResponse::build(status);
+        // RFC 7235: a 401 response SHOULD carry WWW-Authenticate so clients
+        // (and browser devtools) know which scheme to retry with, rather
+        // than just a bare status code.
+        if status == StatusCode::UNAUTHORIZED {
+            response.insert_header((actix_web::http::header::WWW_AUTHENTICATE, "Bearer"));
+        }
+        response.json(ApiResponse::<()>::failure(body))
     }
 }
 
 impl AppError {
-    /// Helper function to extract details for the JSON response body.
-    fn get_response_details(&self) -> ErrorResponseOwnedData {
+    /// Builds the structured `{ code, message_key, message, fields }` body
+    /// for this error. `code` and `message_key` are contract-stable (safe to
+    /// branch/localize on); `message` is the English fallback and may be
+    /// reworded freely.
+    fn to_response_body(&self) -> ErrorBody {
         match self {
-            AppError::NotFound(msg) => ErrorResponseOwnedData {
-                error_category: "Not Found",
-                message: Some(msg.clone()),
+            AppError::NotFound { resource, id } => ErrorBody {
+                code: "RESOURCE_NOT_FOUND".to_string(),
+                message_key: format!("errors.{}.not_found", resource),
+                message: format!("{} '{}' not found", resource, id),
+                fields: None,
+                details: None,
+            },
+            AppError::Conflict { field, value } => ErrorBody {
+                // "email"/"user_name" are the only fields the repository
+                // currently reports conflicts on; anything else still gets a
+                // usable, if generic, code rather than a fallback.
+                code: match field.as_str() {
+                    "email" => "DUPLICATE_EMAIL",
+                    "user_name" => "DUPLICATE_USERNAME",
+                    _ => "DUPLICATE_RESOURCE",
+                }
+                .to_string(),
+                message_key: format!("errors.user.conflict.{}", field),
+                message: format!("{} '{}' already exists", field, value),
+                fields: None,
                 details: None,
             },
-            AppError::Conflict(msg) => ErrorResponseOwnedData {
-                error_category: "Conflict",
-                message: Some(msg.clone()),
+            AppError::ReferencedResourceMissing { field, constraint } => ErrorBody {
+                code: "REFERENCED_RESOURCE_MISSING".to_string(),
+                message_key: "errors.resource.fk_violation".to_string(),
+                message: format!("{} references a resource that does not exist", field),
+                fields: None,
+                details: Some(
+                    serde_json::json!({ "field": field, "constraint": constraint }),
+                ),
+            },
+            AppError::ConstraintViolation { field, constraint } => ErrorBody {
+                code: "CONSTRAINT_VIOLATION".to_string(),
+                message_key: "errors.request.constraint_violation".to_string(),
+                message: format!("'{}' violates a database constraint", field),
+                fields: None,
+                details: Some(
+                    serde_json::json!({ "field": field, "constraint": constraint }),
+                ),
+            },
+            AppError::BadRequest(msg) => ErrorBody {
+                code: "BAD_REQUEST".to_string(),
+                message_key: "errors.request.bad_request".to_string(),
+                message: msg.clone(),
+                fields: None,
                 details: None,
             },
-            AppError::BadRequest(msg) => ErrorResponseOwnedData {
-                error_category: "Bad Request",
-                message: Some(msg.clone()),
+            AppError::Unauthorized(msg) => ErrorBody {
+                code: "UNAUTHORIZED".to_string(),
+                message_key: "errors.auth.unauthorized".to_string(),
+                message: msg.clone(),
+                fields: None,
                 details: None,
             },
-            AppError::Database(SqlxError::RowNotFound) => ErrorResponseOwnedData {
-                error_category: "Not Found",
-                message: Some("The requested resource was not found.".to_string()),
+            AppError::Forbidden(msg) => ErrorBody {
+                code: "FORBIDDEN".to_string(),
+                message_key: "errors.auth.forbidden".to_string(),
+                message: msg.clone(),
+                fields: None,
                 details: None,
             },
-            AppError::Database(err) if is_unique_constraint_violation(err) => {
-                ErrorResponseOwnedData {
-                    error_category: "Conflict",
-                    message: Some(
-                        "A resource with the provided identifier(s) already exists.".to_string(),
-                    ),
-                    details: None,
-                }
-            }
-            AppError::Validation(errors) => ErrorResponseOwnedData {
-                error_category: "Validation Error", // Or "Bad Request"
-                message: Some("Input validation failed. Check details.".to_string()),
-                // Serialize the ValidationErrors into a serde_json::Value for the details field
-                details: serde_json::to_value(errors).ok(), // Use .ok() to ignore serialization errors (shouldn't happen)
+            AppError::Database(SqlxError::RowNotFound) => ErrorBody {
+                code: "RESOURCE_NOT_FOUND".to_string(),
+                message_key: "errors.resource.not_found".to_string(),
+                message: "The requested resource was not found.".to_string(),
+                fields: None,
+                details: None,
+            },
+            AppError::Validation(errors) => ErrorBody {
+                code: "VALIDATION_FAILED".to_string(),
+                message_key: "errors.request.validation_failed".to_string(),
+                message: "Input validation failed. Check fields for details.".to_string(),
+                fields: Some(errors.clone()),
+                details: None,
+            },
+            // The DB is reachable enough to report an error but the query
+            // itself failed unexpectedly (not a RowNotFound, not a
+            // constraint violation the repository already mapped to a
+            // typed variant above) - the closest stable code we can give a
+            // client is "the data layer is unavailable".
+            AppError::Database(_) => ErrorBody {
+                code: "DB_UNAVAILABLE".to_string(),
+                message_key: "errors.server.db_unavailable".to_string(),
+                message: "An unexpected error occurred on the server.".to_string(),
+                fields: None,
+                details: None,
             },
             // Generic Server Errors (avoid leaking internal details)
-            AppError::Database(_)
-            | AppError::Internal(_)
+            AppError::Internal(_)
             | AppError::Io(_)
             | AppError::Anyhow(_)
-            | AppError::Config(_) => ErrorResponseOwnedData {
-                error_category: "Internal Server Error",
-                message: Some("An unexpected error occurred on the server.".to_string()),
+            | AppError::Migration(_)
+            | AppError::Config(_) => ErrorBody {
+                code: "INTERNAL_ERROR".to_string(),
+                message_key: "errors.server.internal".to_string(),
+                message: "An unexpected error occurred on the server.".to_string(),
+                fields: None,
                 details: None,
             },
         }
     }
 }
 
-/// Checks if an sqlx::Error represents a unique constraint violation (Postgres specific).
-/// This might need adjustment based on the specific database and driver.
-fn is_unique_constraint_violation(err: &SqlxError) -> bool {
-    if let SqlxError::Database(db_err) = err {
-        // Postgres unique violation code is "23505"
-        // Use the trait method `code()` which returns Option<&str>
-        if db_err.code().is_some_and(|code| code == "23505") {
-            return true;
-        }
-    }
-    false
-}
-
 // --- Type Alias for Results ---
 
 /// A convenience type alias for `Result<T, AppError>`.
@@ -222,10 +475,12 @@ mod tests {
     use std::borrow::Cow;
 
     // Mock Database Error for testing status codes
-    #[derive(Debug)]
+    #[derive(Debug, Default)]
     struct MockDbError {
         code: Option<String>,
         message: String,
+        constraint: Option<String>,
+        table: Option<String>,
     }
 
     impl std::error::Error for MockDbError {}
@@ -246,6 +501,14 @@ mod tests {
             self.code.as_deref().map(Cow::Borrowed)
         }
 
+        fn constraint(&self) -> Option<&str> {
+            self.constraint.as_deref()
+        }
+
+        fn table(&self) -> Option<&str> {
+            self.table.as_deref()
+        }
+
         // Other methods can return None or default values if not needed for the test
         fn kind(&self) -> sqlx::error::ErrorKind {
             sqlx::error::ErrorKind::Other // Or map based on code if necessary
@@ -265,17 +528,53 @@ mod tests {
     #[test]
     fn test_status_codes() {
         assert_eq!(
-            AppError::NotFound("test".into()).status_code(),
+            AppError::NotFound {
+                resource: "user",
+                id: "test".to_string()
+            }
+            .status_code(),
             StatusCode::NOT_FOUND
         );
         assert_eq!(
-            AppError::Conflict("test".into()).status_code(),
+            AppError::Conflict {
+                field: "email".to_string(),
+                value: "test".to_string()
+            }
+            .status_code(),
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            AppError::ReferencedResourceMissing {
+                field: "credentials".to_string(),
+                constraint: "credentials_user_id_fkey".to_string()
+            }
+            .status_code(),
             StatusCode::CONFLICT
         );
+        assert_eq!(
+            AppError::ConstraintViolation {
+                field: "users".to_string(),
+                constraint: "users_first_name_not_null".to_string()
+            }
+            .status_code(),
+            StatusCode::BAD_REQUEST
+        );
         assert_eq!(
             AppError::BadRequest("test".into()).status_code(),
             StatusCode::BAD_REQUEST
         );
+        assert_eq!(
+            AppError::Unauthorized("test".into()).status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            AppError::Forbidden("test".into()).status_code(),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            AppError::Validation(vec![FieldError::new("email", "is required")]).status_code(),
+            StatusCode::BAD_REQUEST
+        );
         assert_eq!(
             AppError::Internal("test".into()).status_code(),
             StatusCode::INTERNAL_SERVER_ERROR
@@ -301,21 +600,13 @@ mod tests {
             StatusCode::NOT_FOUND
         );
 
-        // Simulate a unique constraint violation error using the mock
-        let unique_db_error = MockDbError {
+        // A raw DB error that was never translated to a typed Conflict still
+        // falls back to 500, since the repository is responsible for mapping
+        // unique-constraint violations before they reach this layer.
+        let other_mock_db_error = MockDbError {
             code: Some("23505".to_string()),
             message: "duplicate key".to_string(),
-        };
-        let unique_error = SqlxError::Database(Box::new(unique_db_error));
-        assert_eq!(
-            AppError::Database(unique_error).status_code(),
-            StatusCode::CONFLICT
-        );
-
-        // Simulate another DB error using the mock (no specific code)
-        let other_mock_db_error = MockDbError {
-            code: Some("xxxxx".to_string()), // Different code
-            message: "some other db error".to_string(),
+            ..Default::default()
         };
         let other_db_error_mocked = SqlxError::Database(Box::new(other_mock_db_error));
         assert_eq!(
@@ -331,59 +622,157 @@ mod tests {
         );
     }
 
-    // Helper needs to be async because `to_bytes` is async
-    async fn get_error_response_body(app_error: AppError) -> ErrorResponse {
+    // Helper needs to be async because `to_bytes` is async. Unwraps straight
+    // to the `ErrorBody`, since every `error_response()` output has
+    // `success: false` and a populated `error` by construction.
+    async fn get_error_response_body(app_error: AppError) -> ErrorBody {
         let response = app_error.error_response();
         let body_bytes = to_bytes(response.into_body()).await.unwrap();
-        serde_json::from_slice(&body_bytes).unwrap_or_else(|e| {
+        let envelope: ApiResponse<()> = serde_json::from_slice(&body_bytes).unwrap_or_else(|e| {
             panic!(
                 "Failed to deserialize error response body: {:?}, Body: {}",
                 e,
                 String::from_utf8_lossy(&body_bytes)
             )
-        })
+        });
+        assert!(!envelope.success, "error response must have success: false");
+        envelope.error.expect("error response must carry an error body")
     }
 
     #[actix_web::test]
     async fn test_error_response_formatting() {
         // Not Found
-        let not_found_err = AppError::NotFound("User 123 not found".to_string());
-        // Use .await when calling the async helper function
+        let not_found_err = AppError::NotFound {
+            resource: "user",
+            id: "123".to_string(),
+        };
         let body_nf = get_error_response_body(not_found_err).await;
-        assert_eq!(body_nf.status, 404);
-        assert_eq!(body_nf.error, "Not Found");
-        assert_eq!(body_nf.message, Some("User 123 not found".to_string()));
-        assert!(body_nf.details.is_none());
+        assert_eq!(body_nf.code, "RESOURCE_NOT_FOUND");
+        assert_eq!(body_nf.message_key, "errors.user.not_found");
+        assert_eq!(body_nf.message, "user '123' not found");
+        assert!(body_nf.fields.is_none());
 
         // Conflict
-        let conflict_err = AppError::Conflict("Email already exists".to_string());
-        let body_c = get_error_response_body(conflict_err).await; // Use .await
-        assert_eq!(body_c.status, 409);
-        assert_eq!(body_c.error, "Conflict");
-        assert_eq!(body_c.message, Some("Email already exists".to_string()));
-        assert!(body_c.details.is_none());
+        let conflict_err = AppError::Conflict {
+            field: "email".to_string(),
+            value: "dup@example.com".to_string(),
+        };
+        let body_c = get_error_response_body(conflict_err).await;
+        assert_eq!(body_c.code, "DUPLICATE_EMAIL");
+        assert_eq!(body_c.message_key, "errors.user.conflict.email");
+        assert_eq!(
+            body_c.message,
+            "email 'dup@example.com' already exists"
+        );
+        assert!(body_c.fields.is_none());
+
+        // Validation
+        let validation_err =
+            AppError::Validation(vec![FieldError::new("userName", "is required")]);
+        let body_v = get_error_response_body(validation_err).await;
+        assert_eq!(body_v.code, "VALIDATION_FAILED");
+        assert_eq!(
+            body_v.fields,
+            Some(vec![FieldError::new("userName", "is required")])
+        );
 
         // Internal Server Error (generic message)
         let internal_err = AppError::Internal("Something broke!".to_string());
-        let body_i = get_error_response_body(internal_err).await; // Use .await
-        assert_eq!(body_i.status, 500);
-        assert_eq!(body_i.error, "Internal Server Error");
-        assert!(body_i.message.is_some());
+        let body_i = get_error_response_body(internal_err).await;
+        assert_eq!(body_i.code, "INTERNAL_ERROR");
         assert_eq!(
-            body_i.message.unwrap(),
+            body_i.message,
             "An unexpected error occurred on the server."
         ); // Generic message
-        assert!(body_i.details.is_none());
+        assert!(body_i.fields.is_none());
 
         // Database Row Not Found (maps to 404 Not Found)
         let db_not_found_err = AppError::Database(SqlxError::RowNotFound);
-        let body_db_nf = get_error_response_body(db_not_found_err).await; // Use .await
-        assert_eq!(body_db_nf.status, 404);
-        assert_eq!(body_db_nf.error, "Not Found");
+        let body_db_nf = get_error_response_body(db_not_found_err).await;
+        assert_eq!(body_db_nf.code, "RESOURCE_NOT_FOUND");
         assert_eq!(
             body_db_nf.message,
-            Some("The requested resource was not found.".to_string())
+            "The requested resource was not found."
+        );
+        assert!(body_db_nf.fields.is_none());
+
+        // A raw DB error that isn't RowNotFound (and wasn't pre-mapped to a
+        // typed Conflict by the repository) gets a distinct, stable code
+        // from a generic Internal/Io/Anyhow error.
+        let other_mock_db_error = MockDbError {
+            code: Some("08006".to_string()),
+            message: "connection reset".to_string(),
+            ..Default::default()
+        };
+        let db_unavailable_err =
+            AppError::Database(SqlxError::Database(Box::new(other_mock_db_error)));
+        let body_db_unavail = get_error_response_body(db_unavailable_err).await;
+        assert_eq!(body_db_unavail.code, "DB_UNAVAILABLE");
+    }
+
+    #[test]
+    fn test_api_response_success_and_failure_shapes() {
+        let success = ApiResponse::success(42);
+        let success_json = serde_json::to_value(&success).unwrap();
+        assert_eq!(success_json, serde_json::json!({ "success": true, "data": 42 }));
+
+        let failure: ApiResponse<i32> =
+            ApiResponse::failure(AppError::Forbidden("nope".to_string()).to_response_body());
+        let failure_json = serde_json::to_value(&failure).unwrap();
+        assert_eq!(failure_json["success"], serde_json::json!(false));
+        assert!(failure_json.get("data").is_none());
+        assert_eq!(failure_json["error"]["code"], serde_json::json!("FORBIDDEN"));
+    }
+
+    #[actix_web::test]
+    async fn test_unauthorized_response_has_www_authenticate_header() {
+        let response = AppError::Unauthorized("bad token".to_string()).error_response();
+        assert_eq!(
+            response.headers().get(actix_web::http::header::WWW_AUTHENTICATE),
+            Some(&actix_web::http::HeaderValue::from_static("Bearer"))
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_forbidden_response_has_no_www_authenticate_header() {
+        // WWW-Authenticate only makes sense for 401; a 403 means the caller
+        // is already authenticated but lacks permission, so re-prompting
+        // for credentials would be misleading.
+        let response = AppError::Forbidden("no permission".to_string()).error_response();
+        assert!(response
+            .headers()
+            .get(actix_web::http::header::WWW_AUTHENTICATE)
+            .is_none());
+    }
+
+    #[actix_web::test]
+    async fn test_constraint_violation_details_carry_field_and_constraint() {
+        let fk_err = AppError::ReferencedResourceMissing {
+            field: "credentials".to_string(),
+            constraint: "credentials_user_id_fkey".to_string(),
+        };
+        let body_fk = get_error_response_body(fk_err).await;
+        assert_eq!(body_fk.code, "REFERENCED_RESOURCE_MISSING");
+        assert_eq!(
+            body_fk.details,
+            Some(serde_json::json!({
+                "field": "credentials",
+                "constraint": "credentials_user_id_fkey"
+            }))
+        );
+
+        let check_err = AppError::ConstraintViolation {
+            field: "users".to_string(),
+            constraint: "users_first_name_not_null".to_string(),
+        };
+        let body_check = get_error_response_body(check_err).await;
+        assert_eq!(body_check.code, "CONSTRAINT_VIOLATION");
+        assert_eq!(
+            body_check.details,
+            Some(serde_json::json!({
+                "field": "users",
+                "constraint": "users_first_name_not_null"
+            }))
         );
-        assert!(body_db_nf.details.is_none());
     }
 }
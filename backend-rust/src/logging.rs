@@ -1,12 +1,39 @@
-use tracing::Level;
-use tracing::Subscriber;
+use serde::Deserialize;
+use tracing::{Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
 use tracing_subscriber::{filter::LevelFilter, fmt::time::UtcTime, EnvFilter, FmtSubscriber};
 
-/// Configures the global tracing subscriber for structured JSON logging.
+/// Output format for log lines, selectable via `--log-format`/`APP_LOG_FORMAT`
+/// (see `config::Config::log_format`). Defaults to `Pretty` for local
+/// development; deployments emitting to a log aggregator should set `Json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Multi-line, human-friendly output with ANSI color (local development).
+    Pretty,
+    /// Single-line, human-friendly output (local development, less noisy).
+    Compact,
+    /// Single-line JSON, one object per log line; lines logged inside a
+    /// request's span carry its correlation `request_id` field
+    /// (production; machine-parseable).
+    Json,
+}
+
+/// Configures the global tracing subscriber.
+///
+/// Logs are routed through a non-blocking writer (`tracing_appender`) so
+/// request handlers never block on slow stdout/file I/O. If `LOG_DIR` is
+/// set, logs are written there with daily rotation; otherwise they go to
+/// stdout. The returned `WorkerGuard` flushes buffered logs on drop, so the
+/// caller must keep it alive for the process lifetime.
 // The return type needs to handle the complex type returned by .finish()
-// Using `impl Subscriber + Send + Sync` is idiomatic.
-pub fn build_subscriber(app_name: &str, verbosity: u8) -> impl Subscriber + Send + Sync {
-    // pub fn configure_logging(app_name: &str, verbosity: u8) {
+// Using `Box<dyn Subscriber + Send + Sync>` lets us pick between the
+// pretty/compact/json formatter variants, which are otherwise distinct types.
+pub fn build_subscriber(
+    app_name: &str,
+    verbosity: u8,
+    format: LogFormat,
+) -> (Box<dyn Subscriber + Send + Sync>, WorkerGuard) {
     let app_crate_name = app_name.replace('-', "_"); // Crates use underscores
 
     // Determine the base level from verbosity flags
@@ -55,21 +82,42 @@ pub fn build_subscriber(app_name: &str, verbosity: u8) -> impl Subscriber + Send
             }
         }
     };
-    // Build the subscriber stack
-    FmtSubscriber::builder()
+
+    // Route through a non-blocking writer: a daily-rotating file under
+    // LOG_DIR if set, otherwise stdout.
+    let (non_blocking, guard) = match std::env::var("LOG_DIR") {
+        Ok(dir) if !dir.is_empty() => {
+            let file_appender = tracing_appender::rolling::daily(dir, format!("{}.log", app_crate_name));
+            tracing_appender::non_blocking(file_appender)
+        }
+        _ => tracing_appender::non_blocking(std::io::stdout()),
+    };
+
+    let base = FmtSubscriber::builder()
         .with_env_filter(filter)
         .with_target(true) // Log target (module path)
         .with_line_number(true) // Log line numbers
         .with_level(true) // Log level
         .with_timer(UtcTime::rfc_3339()) // Use RFC 3339 timestamp format
-        .json() // Output logs in JSON format
-        .finish() // Build the subscriber part
+        .with_writer(non_blocking);
+
+    let subscriber: Box<dyn Subscriber + Send + Sync> = match format {
+        LogFormat::Pretty => Box::new(base.pretty().finish()),
+        LogFormat::Compact => Box::new(base.compact().finish()),
+        LogFormat::Json => Box::new(base.json().finish()),
+    };
+
+    (subscriber, guard)
 }
 
 // New function to initialize logging for the application
 // This function *will* call set_global_default
-pub fn init_global_subscriber(app_name: &str, verbosity: u8) {
-    let subscriber = build_subscriber(app_name, verbosity);
+/// Initializes the global tracing subscriber and returns its `WorkerGuard`.
+/// The caller must hold onto the guard for the process lifetime (e.g. bind
+/// it to a variable in `main`) so buffered log lines are flushed on drop.
+#[must_use = "dropping the returned WorkerGuard immediately stops background log flushing"]
+pub fn init_global_subscriber(app_name: &str, verbosity: u8, format: LogFormat) -> WorkerGuard {
+    let (subscriber, guard) = build_subscriber(app_name, verbosity, format);
 
     // Attempt to set the global default subscriber
     if let Err(e) = tracing::subscriber::set_global_default(subscriber) {
@@ -84,9 +132,12 @@ pub fn init_global_subscriber(app_name: &str, verbosity: u8) {
     // We log the intended levels based on the logic above.
     tracing::info!(
         verbosity,
+        log_format = ?format,
         // configured_app_level = %app_level,
         // configured_default_level = %default_other_level,
         rust_log_env = std::env::var("RUST_LOG").ok(), // Show if RUST_LOG was present
-        "Logging configured (JSON format)"
+        "Logging configured"
     );
+
+    guard
 }
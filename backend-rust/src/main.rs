@@ -11,14 +11,17 @@ mod database;
 mod domain;
 mod error;
 mod logging;
+mod middleware;
 mod repository;
 mod service;
 
-use crate::api::health::ServerStartTime;
+use crate::api::health::{HealthMonitor, ServerStartTime};
 use crate::config::load_config;
-use crate::database::create_pool;
+use crate::database::{create_pool, create_user_repository};
 use crate::logging::init_global_subscriber;
-use crate::repository::user_repository::UserRepository;
+use crate::middleware::rate_limit::RateLimiter;
+use crate::repository::user_repository::UserRepositoryTrait;
+use crate::service::auth_service::AuthService;
 use crate::service::user_service::UserService;
 // Import state struct
 
@@ -37,40 +40,114 @@ async fn main() -> anyhow::Result<()> {
     let pkg_name = env::var("CARGO_PKG_NAME").expect("CARGO_PKG_NAME env var is not set");
     let pkg_version = env::var("CARGO_PKG_VERSION").expect("CARGO_PKG_VERSION env var is not set");
 
-    // Configure Logging
-    init_global_subscriber(&pkg_name, config.verbose);
+    // Configure Logging. The guard must stay alive for the process lifetime
+    // so the non-blocking writer keeps flushing buffered log lines.
+    let _log_guard = init_global_subscriber(&pkg_name, config.verbose, config.log_format);
     tracing::info!(config = ?config, "Configuration loaded");
 
-    // Create Database Pool
-    let db_pool = Arc::new(create_pool(&config.db.dsn, config.db.max_open_conns).await?);
-    tracing::info!("Database pool created");
+    // Apply schema migrations using a short-lived pool authenticated as the
+    // high-privilege migration role, then drop it immediately. The running
+    // service never holds a connection that can alter schema.
+    // `--db-auto-migrate`/`APP_DB_AUTO_MIGRATE` gates this on the normal
+    // startup path; `--migrate-only` (checked below) always migrates
+    // regardless, since that flag's whole purpose is to apply migrations.
+    if config.db.auto_migrate || config.migrate_only {
+        let migration_pool = create_pool(
+            &config.db.migration_dsn,
+            2,
+            config.db.ca_cert.as_deref(),
+            config.db.tls_insecure_skip_verify,
+        )
+        .await?;
+        database::run_migrations(&migration_pool).await?;
+        migration_pool.close().await;
+        drop(migration_pool);
+    } else {
+        tracing::info!("db.auto_migrate is disabled; skipping schema migrations at startup");
+    }
 
-    // Create Repository (depends on pool)
-    let user_repo = Arc::new(UserRepository::new(db_pool.clone()));
+    if config.migrate_only {
+        tracing::info!("--migrate-only set: migrations applied, exiting without starting the server");
+        return Ok(());
+    }
+
+    // Create the runtime (least-privilege) database pool used for request handling
+    let db_pool = Arc::new(
+        create_pool(
+            &config.db.runtime_dsn,
+            config.db.max_open_conns,
+            config.db.ca_cert.as_deref(),
+            config.db.tls_insecure_skip_verify,
+        )
+        .await?,
+    );
+    tracing::info!("Runtime database pool created");
+
+    // Create Repository, dispatched on `runtime_dsn`'s scheme by
+    // `create_user_repository`: a Postgres DSN gets a `UserRepository`
+    // (opening its own pool independent of `db_pool` above, which the
+    // readiness health check needs regardless of which repository backend is
+    // selected); `sqlite:`/`sqlite::memory:` gets a `SqliteUserRepository`
+    // over a `SqlitePool` instead. Migrations and the readiness check above
+    // stay Postgres-only, so a `sqlite:` `runtime_dsn` still won't boot this
+    // binary end-to-end yet - see that function's doc comment.
+    let user_repo: Arc<dyn UserRepositoryTrait> = create_user_repository(
+        &config.db.runtime_dsn,
+        config.db.max_open_conns,
+        config.db.ca_cert.as_deref(),
+        config.db.tls_insecure_skip_verify,
+    )
+    .await?;
     tracing::info!("User repository created");
 
     // Create Service (depends on repository)
     let user_service = Arc::new(UserService::new(user_repo.clone()));
     tracing::info!("User service created");
 
+    // Create the stateless JWT auth service from the configured secret/lifetime/issuer
+    let auth_service = Arc::new(AuthService::new(
+        config.auth.jwt_secret.clone(),
+        config.auth.jwt_max_age,
+        config.auth.issuer.clone(),
+    ));
+    tracing::info!("Auth service created");
+
     // Prepare state for Actix
     let db_pool_data = web::Data::from(db_pool.clone()); // web::Data wraps Arc
     let user_service_data = web::Data::from(user_service.clone());
+    let auth_service_data = web::Data::from(auth_service.clone());
     let start_time_data = web::Data::new(ServerStartTime { time: start_time });
+    let health_monitor_data = web::Data::new(HealthMonitor::new());
 
     // Start HTTP Server
     let bind_address = format!("{}:{}", config.http.host, config.http.port);
     tracing::info!("Starting server on {}", bind_address);
 
+    // Built once and cloned into every worker so all workers share the same
+    // bucket table; a fresh `RateLimiter` per worker would let each worker
+    // enforce the configured limit independently, multiplying it in effect.
+    let rate_limiter = RateLimiter::new(config.http.rate_limit);
+
     let server = HttpServer::new(move || {
         App::new()
             // Add state
             .app_data(db_pool_data.clone()) // Share DB pool
             .app_data(user_service_data.clone()) // Share User service
+            .app_data(auth_service_data.clone()) // Share Auth service
             .app_data(start_time_data.clone()) // Share start time
+            .app_data(health_monitor_data.clone()) // Share sysinfo sampler
+            // Scopes the call-site trace chain (error::trace_err!) to this
+            // request's handler future, so breadcrumbs from concurrent
+            // requests on the same worker never mix.
+            .wrap(middleware::trace_chain::TraceChain)
+            // Echoes the request id TracingLogger generates back as an
+            // X-Request-Id response header; must wrap *inside* TracingLogger
+            // (registered before it) so that id is already set when it runs.
+            .wrap(middleware::request_id::RequestIdHeader)
             // Add tracing middleware (structured logging per request)
             .wrap(tracing_actix_web::TracingLogger::default())
             // Add other middleware
+            .wrap(rate_limiter.clone())
             .wrap(actix_web::middleware::Compress::default())
             .wrap(actix_web::middleware::NormalizePath::trim())
             .wrap(
@@ -86,6 +163,8 @@ async fn main() -> anyhow::Result<()> {
             )
             // Configure API routes (users, health, etc.)
             .configure(api::configure_api)
+            // Serve the generated OpenAPI spec and an interactive Swagger UI
+            .service(api::openapi::configure_openapi_docs())
             // Default route for 404
             .default_service(
                 web::route().to(|| async { HttpResponse::NotFound().json("Not Found") }),
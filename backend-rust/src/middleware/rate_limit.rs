@@ -0,0 +1,268 @@
+use std::future::{ready, Ready};
+use std::net::IpAddr;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderValue, RETRY_AFTER};
+use actix_web::{Error, HttpResponse};
+use dashmap::DashMap;
+use futures_util::future::LocalBoxFuture;
+
+/// How long an idle per-IP bucket is kept around before the sweep reclaims
+/// it. Keeps memory bounded when many distinct clients churn through.
+const IDLE_EVICTION: Duration = Duration::from_secs(5 * 60);
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+struct RateLimiterState {
+    /// Both the bucket capacity and the refill rate, in requests per minute.
+    capacity: f64,
+    buckets: DashMap<IpAddr, Bucket>,
+}
+
+impl RateLimiterState {
+    /// Refills the caller's bucket for elapsed time and consumes one token
+    /// if available, returning whether the request is allowed.
+    fn try_consume(&self, ip: IpAddr) -> bool {
+        let now = Instant::now();
+        let mut bucket = self.buckets.entry(ip).or_insert_with(|| Bucket {
+            tokens: self.capacity,
+            last_refill: now,
+        });
+
+        let elapsed_secs = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed_secs * self.capacity / 60.0).min(self.capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens < 1.0 {
+            false
+        } else {
+            bucket.tokens -= 1.0;
+            true
+        }
+    }
+}
+
+/// Actix middleware enforcing a per-client-IP requests-per-minute limit via a
+/// lazily-refilled token bucket. Buckets are keyed by the first hop of
+/// `X-Forwarded-For` when present, falling back to the connection's peer
+/// address. Requests over the limit are rejected with `429 Too Many
+/// Requests` and a `Retry-After` header rather than forwarded to the
+/// wrapped service.
+///
+/// Construct once and share the same instance across workers (it's a thin
+/// `Clone` handle around an `Arc`), the way `main.rs` already shares
+/// `web::Data` handles — a fresh instance per worker would give every
+/// worker its own bucket table and silently multiply the effective limit.
+#[derive(Clone)]
+pub struct RateLimiter {
+    state: Arc<RateLimiterState>,
+}
+
+impl RateLimiter {
+    /// `requests_per_minute` becomes both the bucket capacity and refill
+    /// rate; pass `config.http.rate_limit`. Spawns a background task that
+    /// periodically evicts idle buckets.
+    pub fn new(requests_per_minute: usize) -> Self {
+        let state = Arc::new(RateLimiterState {
+            capacity: requests_per_minute as f64,
+            buckets: DashMap::new(),
+        });
+        spawn_idle_sweep(state.clone());
+        Self { state }
+    }
+}
+
+fn spawn_idle_sweep(state: Arc<RateLimiterState>) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(IDLE_EVICTION);
+        loop {
+            interval.tick().await;
+            let now = Instant::now();
+            state
+                .buckets
+                .retain(|_, bucket| now.duration_since(bucket.last_refill) < IDLE_EVICTION);
+        }
+    });
+}
+
+/// Reads the first hop of `X-Forwarded-For` when present (the original
+/// client, as opposed to an intermediate proxy), otherwise falls back to the
+/// connection's peer address.
+fn client_ip(req: &ServiceRequest) -> Option<IpAddr> {
+    req.headers()
+        .get("X-Forwarded-For")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(',').next())
+        .and_then(|ip| ip.trim().parse::<IpAddr>().ok())
+        .or_else(|| req.peer_addr().map(|addr| addr.ip()))
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimiter
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RateLimiterMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimiterMiddleware {
+            service: Rc::new(service),
+            state: self.state.clone(),
+        }))
+    }
+}
+
+pub struct RateLimiterMiddleware<S> {
+    service: Rc<S>,
+    state: Arc<RateLimiterState>,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimiterMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        // An unidentifiable caller (no X-Forwarded-For, no peer address) is
+        // let through rather than lumped into one shared bucket, which would
+        // let any client starve every other unidentifiable one.
+        let Some(ip) = client_ip(&req) else {
+            let fut = self.service.call(req);
+            return Box::pin(async move { fut.await.map(|res| res.map_into_left_body()) });
+        };
+
+        if self.state.try_consume(ip) {
+            let fut = self.service.call(req);
+            Box::pin(async move { fut.await.map(|res| res.map_into_left_body()) })
+        } else {
+            tracing::warn!(%ip, "Rate limit exceeded");
+            let response = HttpResponse::TooManyRequests()
+                .insert_header((RETRY_AFTER, HeaderValue::from_static("60")))
+                .finish();
+            Box::pin(async move { Ok(req.into_response(response).map_into_right_body()) })
+        }
+    }
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    fn state(capacity: f64) -> RateLimiterState {
+        RateLimiterState {
+            capacity,
+            buckets: DashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_try_consume_allows_up_to_capacity_then_blocks() {
+        let state = state(3.0);
+        let ip: IpAddr = "203.0.113.1".parse().unwrap();
+
+        assert!(state.try_consume(ip));
+        assert!(state.try_consume(ip));
+        assert!(state.try_consume(ip));
+        assert!(!state.try_consume(ip));
+    }
+
+    #[test]
+    fn test_try_consume_refills_over_time() {
+        let state = state(60.0);
+        let ip: IpAddr = "203.0.113.2".parse().unwrap();
+
+        for _ in 0..60 {
+            assert!(state.try_consume(ip));
+        }
+        assert!(!state.try_consume(ip));
+
+        // 60 req/min means 1 token per second; back-date the bucket by a
+        // second instead of sleeping the test.
+        {
+            let mut bucket = state.buckets.get_mut(&ip).unwrap();
+            bucket.last_refill -= Duration::from_secs(1);
+        }
+        assert!(state.try_consume(ip));
+        assert!(!state.try_consume(ip));
+    }
+
+    #[test]
+    fn test_try_consume_does_not_exceed_capacity() {
+        let state = state(2.0);
+        let ip: IpAddr = "203.0.113.3".parse().unwrap();
+
+        state.try_consume(ip);
+        {
+            let mut bucket = state.buckets.get_mut(&ip).unwrap();
+            bucket.last_refill -= Duration::from_secs(60 * 60);
+        }
+        // An hour of accrual should cap at `capacity`, not overflow past it.
+        assert!(state.try_consume(ip));
+        assert!(state.try_consume(ip));
+        assert!(!state.try_consume(ip));
+    }
+
+    #[test]
+    fn test_try_consume_tracks_distinct_ips_independently() {
+        let state = state(1.0);
+        let a: IpAddr = "203.0.113.4".parse().unwrap();
+        let b: IpAddr = "203.0.113.5".parse().unwrap();
+
+        assert!(state.try_consume(a));
+        assert!(!state.try_consume(a));
+        assert!(state.try_consume(b));
+    }
+
+    #[test]
+    fn test_client_ip_missing_header_falls_back_to_peer_addr() {
+        let req = TestRequest::default().to_srv_request();
+        assert_eq!(req.peer_addr(), None);
+        assert_eq!(client_ip(&req), None);
+    }
+
+    #[test]
+    fn test_client_ip_uses_first_hop_of_forwarded_for() {
+        let req = TestRequest::default()
+            .insert_header(("X-Forwarded-For", "198.51.100.7, 10.0.0.1, 10.0.0.2"))
+            .to_srv_request();
+        assert_eq!(client_ip(&req), Some("198.51.100.7".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_client_ip_trims_whitespace_around_first_hop() {
+        let req = TestRequest::default()
+            .insert_header(("X-Forwarded-For", "  198.51.100.8  , 10.0.0.1"))
+            .to_srv_request();
+        assert_eq!(client_ip(&req), Some("198.51.100.8".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_client_ip_malformed_forwarded_for_falls_back_to_peer_addr() {
+        let req = TestRequest::default()
+            .insert_header(("X-Forwarded-For", "not-an-ip"))
+            .to_srv_request();
+        assert_eq!(req.peer_addr(), None);
+        assert_eq!(client_ip(&req), None);
+    }
+}
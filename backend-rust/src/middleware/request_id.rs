@@ -0,0 +1,71 @@
+use std::future::{ready, Ready};
+
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::Error;
+use futures_util::future::LocalBoxFuture;
+use tracing_actix_web::RequestId;
+
+/// Echoes the per-request correlation id back to the client as an
+/// `X-Request-Id` response header.
+///
+/// `tracing_actix_web::TracingLogger` already generates a UUID v4
+/// `request_id` per request, stores it as a request extension, and tags the
+/// request's tracing span (and therefore every `tracing::info!` line a
+/// handler logs while handling it) with that id. This middleware just reads
+/// that same id back out and surfaces it to the caller, so a client-reported
+/// issue can be correlated with server-side logs.
+///
+/// Must be registered *inside* `TracingLogger` — i.e. `.wrap()`ped before
+/// it in `main.rs` — so the `RequestId` extension it reads has already been
+/// set by the time this middleware's `call` runs.
+pub struct RequestIdHeader;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestIdHeader
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestIdHeaderMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestIdHeaderMiddleware { service }))
+    }
+}
+
+pub struct RequestIdHeaderMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdHeaderMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let request_id = req.extensions().get::<RequestId>().copied();
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let mut res = fut.await?;
+            if let Some(request_id) = request_id {
+                if let Ok(value) = HeaderValue::from_str(&request_id.to_string()) {
+                    res.headers_mut().insert(HeaderName::from_static("x-request-id"), value);
+                }
+            }
+            Ok(res)
+        })
+    }
+}
@@ -0,0 +1,53 @@
+use std::future::{ready, Ready};
+
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::Error;
+use futures_util::future::LocalBoxFuture;
+
+use crate::error::with_trace_scope;
+
+/// Scopes the call-site trace chain (`error::trace_err!`/`error::Trace`) to
+/// exactly one request, so breadcrumbs from concurrent requests handled by
+/// the same worker never mix. Only needs to wrap the handler it sits in
+/// front of, so its position relative to the other middleware in
+/// `main.rs` doesn't matter; registered closest to the app's routes.
+pub struct TraceChain;
+
+impl<S, B> Transform<S, ServiceRequest> for TraceChain
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = TraceChainMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(TraceChainMiddleware { service }))
+    }
+}
+
+pub struct TraceChainMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for TraceChainMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let fut = self.service.call(req);
+        Box::pin(with_trace_scope(fut))
+    }
+}
@@ -0,0 +1,2 @@
+pub mod user_repository;
+pub mod user_repository_sqlite;
@@ -0,0 +1,559 @@
+use async_trait::async_trait;
+use sqlx::{types::time::OffsetDateTime, Error as SqlxError, SqlitePool};
+use std::sync::Arc;
+
+use crate::domain::models::{Credential, User, UserListQuery, UserStatus};
+use crate::error::{AppError, AppResult};
+use crate::repository::user_repository::UserRepositoryTrait;
+
+/// SQLite reports unique-constraint violations as `SQLITE_CONSTRAINT` with
+/// extended code `2067` (`SQLITE_CONSTRAINT_UNIQUE`), rather than Postgres's
+/// `23505`. Unlike Postgres, sqlx's SQLite backend doesn't populate
+/// `DatabaseError::table()`/`constraint()`, so the offending column has to be
+/// read out of the driver's message text instead (e.g. `"UNIQUE constraint
+/// failed: users.user_name"`). Returns `None` for any other error, same as
+/// `user_repository::conflict_from_unique_violation`.
+fn conflict_from_unique_violation(err: &SqlxError, user_name: &str, email: &str) -> Option<AppError> {
+    let SqlxError::Database(db_err) = err else {
+        return None;
+    };
+    if db_err.code().as_deref() != Some("2067") {
+        return None;
+    }
+    let message = db_err.message();
+    if message.contains("users.user_name") {
+        Some(AppError::Conflict {
+            field: "user_name".to_string(),
+            value: user_name.to_string(),
+        })
+    } else if message.contains("users.email") {
+        Some(AppError::Conflict {
+            field: "email".to_string(),
+            value: email.to_string(),
+        })
+    } else {
+        None
+    }
+}
+
+/// SQLite-backed implementation of `UserRepositoryTrait`. Lets the demo (and
+/// its test suite) run against an in-memory `sqlite::memory:` database
+/// instead of a live Postgres instance; production keeps using
+/// `UserRepository`. Query shape mirrors `UserRepository` closely so the two
+/// stay easy to compare, but uses the runtime `query_as`/`query` builders
+/// rather than the `query_as!` compile-time macro, since that macro checks
+/// against a single `DATABASE_URL` schema and can't target two backends at once.
+#[derive(Debug, Clone)]
+pub struct SqliteUserRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl SqliteUserRepository {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl UserRepositoryTrait for SqliteUserRepository {
+    async fn list(&self) -> AppResult<Vec<User>> {
+        tracing::debug!("SqliteRepository: Fetching all users");
+        sqlx::query_as::<_, User>(
+            r#"
+            SELECT
+                user_id as id, user_name, first_name, last_name, email,
+                user_status, department, role, created_at, updated_at
+            FROM users WHERE deleted_at IS NULL ORDER BY user_id ASC
+            "#,
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(error.cause_chain = ?e, "SqliteRepository: Failed to list users");
+            AppError::Database(e)
+        })
+    }
+
+    async fn list_including_deleted(&self) -> AppResult<Vec<User>> {
+        tracing::debug!("SqliteRepository: Fetching all users (including soft-deleted)");
+        sqlx::query_as::<_, User>(
+            r#"
+            SELECT
+                user_id as id, user_name, first_name, last_name, email,
+                user_status, department, role, created_at, updated_at
+            FROM users ORDER BY user_id ASC
+            "#,
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(error.cause_chain = ?e, "SqliteRepository: Failed to list users (including soft-deleted)");
+            AppError::Database(e)
+        })
+    }
+
+    async fn restore(&self, id: i64) -> AppResult<User> {
+        tracing::debug!(user_id = id, "SqliteRepository: Restoring soft-deleted user");
+        sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users SET deleted_at = NULL
+            WHERE user_id = ? AND deleted_at IS NOT NULL
+            RETURNING
+                user_id as id, user_name, first_name, last_name, email,
+                user_status, department, role, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(error.cause_chain = ?e, user_id = id, "SqliteRepository: Failed to restore user");
+            AppError::Database(e)
+        })?
+        .ok_or_else(|| AppError::NotFound { resource: "user", id: id.to_string() })
+    }
+
+    async fn list_paginated(&self, query: &UserListQuery) -> AppResult<(Vec<User>, i64)> {
+        // `query` is ignored: no search, sorting or paging is applied. Every
+        // active user comes back in one page, ordered as `list` orders them,
+        // with `total` equal to that count. `MockUserRepository` covers
+        // paginated-list tests without a DB.
+        let all = self.list().await?;
+        let total = all.len() as i64;
+        Ok((all, total))
+    }
+
+    async fn get_by_id(&self, id: i64) -> AppResult<User> {
+        tracing::debug!(user_id = id, "SqliteRepository: Fetching user by ID");
+        sqlx::query_as::<_, User>(
+            r#"
+            SELECT
+                user_id as id, user_name, first_name, last_name, email,
+                user_status, department, role, created_at, updated_at
+            FROM users WHERE user_id = ? AND deleted_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(error.cause_chain = ?e, user_id = id, "SqliteRepository: Failed to fetch user by ID");
+            AppError::Database(e)
+        })?
+        .ok_or_else(|| AppError::NotFound { resource: "user", id: id.to_string() })
+    }
+
+    async fn get_by_user_name(&self, user_name: &str) -> AppResult<User> {
+        tracing::debug!(user_name, "SqliteRepository: Fetching user by username");
+        sqlx::query_as::<_, User>(
+            r#"
+            SELECT
+                user_id as id, user_name, first_name, last_name, email,
+                user_status, department, role, created_at, updated_at
+            FROM users WHERE user_name = ? AND deleted_at IS NULL
+            "#,
+        )
+        .bind(user_name)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(error.cause_chain = ?e, user_name, "SqliteRepository: Failed to fetch user by username");
+            AppError::Database(e)
+        })?
+        .ok_or_else(|| AppError::NotFound { resource: "user", id: user_name.to_string() })
+    }
+
+    async fn create(
+        &self,
+        user_name: &str,
+        first_name: &str,
+        last_name: &str,
+        email: &str,
+        user_status: UserStatus,
+        department: Option<&str>,
+    ) -> AppResult<User> {
+        tracing::debug!(user_name, email, "SqliteRepository: Creating new user");
+        let now = OffsetDateTime::now_utc();
+
+        sqlx::query_as::<_, User>(
+            r#"
+            INSERT INTO users (user_name, first_name, last_name, email, user_status, department, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING
+                user_id as id, user_name, first_name, last_name, email,
+                user_status, department, role, created_at, updated_at
+            "#,
+        )
+        .bind(user_name)
+        .bind(first_name)
+        .bind(last_name)
+        .bind(email)
+        .bind(user_status)
+        .bind(department)
+        .bind(now)
+        .bind(now)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| {
+            if let Some(conflict) = conflict_from_unique_violation(&e, user_name, email) {
+                tracing::warn!(user_name, email, "SqliteRepository: Unique constraint violation during create");
+                return conflict;
+            }
+            tracing::error!(error.cause_chain = ?e, user_name, email, "SqliteRepository: Failed to create user");
+            AppError::Database(e)
+        })
+    }
+
+    async fn update(
+        &self,
+        id: i64,
+        user_name: &str,
+        first_name: &str,
+        last_name: &str,
+        email: &str,
+        user_status: UserStatus,
+        department: Option<&str>,
+    ) -> AppResult<User> {
+        tracing::debug!(user_id = id, user_name, email, "SqliteRepository: Updating user");
+        let now = OffsetDateTime::now_utc();
+
+        sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET user_name = ?, first_name = ?, last_name = ?, email = ?,
+                user_status = ?, department = ?, updated_at = ?
+            WHERE user_id = ? AND deleted_at IS NULL
+            RETURNING
+                user_id as id, user_name, first_name, last_name, email,
+                user_status, department, role, created_at, updated_at
+            "#,
+        )
+        .bind(user_name)
+        .bind(first_name)
+        .bind(last_name)
+        .bind(email)
+        .bind(user_status)
+        .bind(department)
+        .bind(now)
+        .bind(id)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(|e| {
+            if let Some(conflict) = conflict_from_unique_violation(&e, user_name, email) {
+                tracing::warn!(user_id = id, user_name, email, "SqliteRepository: Unique constraint violation during update");
+                return conflict;
+            }
+            tracing::error!(error.cause_chain = ?e, user_id = id, "SqliteRepository: Failed to update user");
+            AppError::Database(e)
+        })?
+        .ok_or_else(|| AppError::NotFound { resource: "user", id: id.to_string() })
+    }
+
+    async fn upsert(
+        &self,
+        user_name: &str,
+        first_name: &str,
+        last_name: &str,
+        email: &str,
+        user_status: UserStatus,
+        department: Option<&str>,
+    ) -> AppResult<User> {
+        tracing::debug!(user_name, email, "SqliteRepository: Upserting user");
+        let now = OffsetDateTime::now_utc();
+
+        sqlx::query_as::<_, User>(
+            r#"
+            INSERT INTO users (user_name, first_name, last_name, email, user_status, department, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (user_name) DO UPDATE SET
+                first_name = excluded.first_name,
+                last_name = excluded.last_name,
+                email = excluded.email,
+                user_status = excluded.user_status,
+                department = excluded.department,
+                updated_at = excluded.updated_at
+            RETURNING
+                user_id as id, user_name, first_name, last_name, email,
+                user_status, department, role, created_at, updated_at
+            "#,
+        )
+        .bind(user_name)
+        .bind(first_name)
+        .bind(last_name)
+        .bind(email)
+        .bind(user_status)
+        .bind(department)
+        .bind(now)
+        .bind(now)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| {
+            if let Some(conflict) = conflict_from_unique_violation(&e, user_name, email) {
+                tracing::warn!(user_name, email, "SqliteRepository: Unique constraint violation during upsert");
+                return conflict;
+            }
+            tracing::error!(error.cause_chain = ?e, user_name, email, "SqliteRepository: Failed to upsert user");
+            AppError::Database(e)
+        })
+    }
+
+    async fn delete(&self, id: i64) -> AppResult<()> {
+        tracing::debug!(user_id = id, "SqliteRepository: Soft-deleting user");
+        let result = sqlx::query(
+            "UPDATE users SET deleted_at = CURRENT_TIMESTAMP WHERE user_id = ? AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(error.cause_chain = ?e, user_id = id, "SqliteRepository: Failed to delete user");
+            AppError::Database(e)
+        })?;
+
+        if result.rows_affected() == 0 {
+            Err(AppError::NotFound { resource: "user", id: id.to_string() })
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn exists_by_user_name(&self, user_name: &str) -> AppResult<bool> {
+        tracing::debug!(user_name, "SqliteRepository: Checking existence by username");
+        sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM users WHERE user_name = ? AND deleted_at IS NULL LIMIT 1)",
+        )
+        .bind(user_name)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(error.cause_chain = ?e, user_name, "SqliteRepository: Failed to check username existence");
+            AppError::Database(e)
+        })
+    }
+
+    async fn exists_by_email(&self, email: &str, exclude_id: i64) -> AppResult<bool> {
+        tracing::debug!(email, exclude_id, "SqliteRepository: Checking existence by email");
+        sqlx::query_scalar::<_, bool>(
+            r#"SELECT EXISTS (
+                SELECT 1 FROM users
+                WHERE email = ? AND deleted_at IS NULL AND (? = 0 OR user_id != ?)
+                LIMIT 1
+            )"#,
+        )
+        .bind(email)
+        .bind(exclude_id)
+        .bind(exclude_id)
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(error.cause_chain = ?e, email, exclude_id, "SqliteRepository: Failed to check email existence");
+            AppError::Database(e)
+        })
+    }
+
+    async fn get_credential(&self, user_id: i64) -> AppResult<Option<Credential>> {
+        tracing::debug!(user_id, "SqliteRepository: Fetching credential");
+        sqlx::query_as::<_, Credential>("SELECT user_id, hash FROM credentials WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!(error.cause_chain = ?e, user_id, "SqliteRepository: Failed to fetch credential");
+                AppError::Database(e)
+            })
+    }
+
+    async fn set_credential(&self, user_id: i64, hash: &str) -> AppResult<()> {
+        tracing::debug!(user_id, "SqliteRepository: Upserting credential");
+        sqlx::query(
+            r#"
+            INSERT INTO credentials (user_id, hash)
+            VALUES (?, ?)
+            ON CONFLICT (user_id) DO UPDATE SET hash = excluded.hash
+            "#,
+        )
+        .bind(user_id)
+        .bind(hash)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(error.cause_chain = ?e, user_id, "SqliteRepository: Failed to upsert credential");
+            AppError::Database(e)
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::Role;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    /// Spins up a fresh in-memory SQLite database with just enough schema to
+    /// exercise `SqliteUserRepository` - no external DB required, which is
+    /// the whole point of this backend per the struct's doc comment above.
+    /// `sqlite::memory:` gives each connection its own private database, so
+    /// the pool is capped at one connection; a bigger pool would silently
+    /// hand a second query a brand-new, empty database.
+    async fn setup_db() -> Arc<SqlitePool> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to create in-memory sqlite pool");
+
+        sqlx::query(
+            r#"
+            CREATE TABLE users (
+                user_id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_name TEXT NOT NULL UNIQUE,
+                first_name TEXT NOT NULL,
+                last_name TEXT NOT NULL,
+                email TEXT NOT NULL UNIQUE,
+                user_status TEXT NOT NULL,
+                department TEXT,
+                role TEXT NOT NULL DEFAULT 'user',
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                deleted_at TEXT
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .expect("failed to create users table");
+
+        sqlx::query(
+            r#"
+            CREATE TABLE credentials (
+                user_id INTEGER PRIMARY KEY REFERENCES users (user_id) ON DELETE CASCADE,
+                hash TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .expect("failed to create credentials table");
+
+        Arc::new(pool)
+    }
+
+    #[tokio::test]
+    async fn test_create_and_get_by_id() {
+        let repo = SqliteUserRepository::new(setup_db().await);
+
+        let created = repo
+            .create(
+                "sqlite_user",
+                "Sqlite",
+                "User",
+                "sqlite.user@example.com",
+                UserStatus::Active,
+                Some("Eng"),
+            )
+            .await
+            .expect("create should succeed");
+        assert!(created.id > 0);
+        assert_eq!(created.user_name, "sqlite_user");
+        assert_eq!(created.role, Role::User); // column default
+
+        let fetched = repo
+            .get_by_id(created.id)
+            .await
+            .expect("get_by_id should succeed");
+        assert_eq!(fetched.email, "sqlite.user@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_create_duplicate_user_name_reports_conflict() {
+        let repo = SqliteUserRepository::new(setup_db().await);
+
+        repo.create("dup_user", "A", "A", "a@example.com", UserStatus::Active, None)
+            .await
+            .unwrap();
+        let err = repo
+            .create("dup_user", "B", "B", "b@example.com", UserStatus::Active, None)
+            .await
+            .unwrap_err();
+
+        match err {
+            AppError::Conflict { field, value } => {
+                assert_eq!(field, "user_name");
+                assert_eq!(value, "dup_user");
+            }
+            other => panic!("expected AppError::Conflict, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_is_soft_and_get_by_id_then_not_found() {
+        let repo = SqliteUserRepository::new(setup_db().await);
+
+        let created = repo
+            .create("to_delete", "A", "A", "to.delete@example.com", UserStatus::Active, None)
+            .await
+            .unwrap();
+        repo.delete(created.id).await.expect("delete should succeed");
+
+        let err = repo.get_by_id(created.id).await.unwrap_err();
+        assert!(matches!(err, AppError::NotFound { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_update_on_soft_deleted_id_returns_not_found() {
+        let repo = SqliteUserRepository::new(setup_db().await);
+
+        let created = repo
+            .create("to_update", "A", "A", "to.update@example.com", UserStatus::Active, None)
+            .await
+            .unwrap();
+        repo.delete(created.id).await.expect("delete should succeed");
+
+        let err = repo
+            .update(
+                created.id,
+                "to_update",
+                "B",
+                "B",
+                "to.update@example.com",
+                UserStatus::Active,
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_exists_by_user_name_and_email() {
+        let repo = SqliteUserRepository::new(setup_db().await);
+
+        repo.create("exists_user", "A", "A", "exists@example.com", UserStatus::Active, None)
+            .await
+            .unwrap();
+
+        assert!(repo.exists_by_user_name("exists_user").await.unwrap());
+        assert!(!repo.exists_by_user_name("nope").await.unwrap());
+        assert!(repo.exists_by_email("exists@example.com", 0).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_set_and_get_credential() {
+        let repo = SqliteUserRepository::new(setup_db().await);
+
+        let created = repo
+            .create("cred_user", "A", "A", "cred@example.com", UserStatus::Active, None)
+            .await
+            .unwrap();
+        assert!(repo.get_credential(created.id).await.unwrap().is_none());
+
+        repo.set_credential(created.id, "argon2-hash").await.unwrap();
+        let credential = repo
+            .get_credential(created.id)
+            .await
+            .unwrap()
+            .expect("credential should exist");
+        assert_eq!(credential.hash, "argon2-hash");
+    }
+}
@@ -0,0 +1,139 @@
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::models::Role;
+use crate::error::{AppError, AppResult};
+
+/// JWT claims issued for an authenticated user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    /// Subject: the authenticated user's ID.
+    pub sub: i64,
+    /// Username, carried in the token so callers don't need a DB round trip.
+    pub user_name: String,
+    /// Authorization role, carried so route guards don't need a DB round trip.
+    pub role: Role,
+    /// Issuer, checked against the configured value on validation.
+    pub iss: String,
+    /// Issued-at timestamp (Unix seconds).
+    pub iat: i64,
+    /// Expiry timestamp (Unix seconds).
+    pub exp: i64,
+}
+
+/// Mints and validates HS256 JWTs for the stateless auth subsystem.
+/// Holds the signing secret directly rather than a key handle, since
+/// `jsonwebtoken`'s `EncodingKey`/`DecodingKey` are cheap to derive per call.
+#[derive(Clone)]
+pub struct AuthService {
+    secret: String,
+    max_age_secs: i64,
+    issuer: String,
+}
+
+impl AuthService {
+    /// Creates a new AuthService from the configured secret, token lifetime, and issuer.
+    pub fn new(secret: String, max_age_secs: u64, issuer: String) -> Self {
+        Self {
+            secret,
+            max_age_secs: max_age_secs as i64,
+            issuer,
+        }
+    }
+
+    /// Mints a signed token for `user_id`/`user_name`/`role`, valid for the configured `jwt_max_age`.
+    pub fn generate_token(&self, user_id: i64, user_name: &str, role: Role) -> AppResult<String> {
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        let claims = Claims {
+            sub: user_id,
+            user_name: user_name.to_string(),
+            role,
+            iss: self.issuer.clone(),
+            iat: now,
+            exp: now + self.max_age_secs,
+        };
+
+        encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(self.secret.as_bytes()),
+        )
+        .map_err(|e| AppError::Internal(format!("Failed to sign JWT: {}", e)))
+    }
+
+    /// Decodes and validates `token`, rejecting expired or wrong-issuer tokens.
+    pub fn validate_token(&self, token: &str) -> AppResult<Claims> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_issuer(&[&self.issuer]);
+        // No clock-skew allowance: a token is rejected as soon as `exp` passes.
+        validation.leeway = 0;
+
+        decode::<Claims>(
+            token,
+            &DecodingKey::from_secret(self.secret.as_bytes()),
+            &validation,
+        )
+        .map(|data| data.claims)
+        .map_err(|e| {
+            tracing::warn!(error = ?e, "AuthService: Token validation failed");
+            AppError::Unauthorized("Invalid or expired token".to_string())
+        })
+    }
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_service() -> AuthService {
+        AuthService::new("test-secret".to_string(), 3600, "test-issuer".to_string())
+    }
+
+    #[test]
+    fn test_generate_and_validate_round_trip() {
+        let service = setup_service();
+        let token = service.generate_token(42, "test_user", Role::User).unwrap();
+
+        let claims = service.validate_token(&token).unwrap();
+        assert_eq!(claims.sub, 42);
+        assert_eq!(claims.user_name, "test_user");
+        assert_eq!(claims.role, Role::User);
+        assert_eq!(claims.iss, "test-issuer");
+        assert!(claims.exp > claims.iat);
+    }
+
+    #[test]
+    fn test_validate_rejects_wrong_secret() {
+        let service = setup_service();
+        let token = service.generate_token(1, "test_user", Role::Admin).unwrap();
+
+        let other_service =
+            AuthService::new("different-secret".to_string(), 3600, "test-issuer".to_string());
+        let result = other_service.validate_token(&token);
+        assert!(matches!(result.err().unwrap(), AppError::Unauthorized(_)));
+    }
+
+    #[test]
+    fn test_validate_rejects_wrong_issuer() {
+        let service = setup_service();
+        let token = service.generate_token(1, "test_user", Role::Admin).unwrap();
+
+        let other_service =
+            AuthService::new("test-secret".to_string(), 3600, "other-issuer".to_string());
+        let result = other_service.validate_token(&token);
+        assert!(matches!(result.err().unwrap(), AppError::Unauthorized(_)));
+    }
+
+    #[test]
+    fn test_validate_rejects_expired_token() {
+        let service = AuthService::new("test-secret".to_string(), 0, "test-issuer".to_string());
+        let token = service.generate_token(1, "test_user", Role::Admin).unwrap();
+
+        // max_age of 0 means exp == iat, so a token minted now is immediately
+        // expired once any wall-clock time elapses before validation.
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        let result = service.validate_token(&token);
+        assert!(matches!(result.err().unwrap(), AppError::Unauthorized(_)));
+    }
+}
@@ -0,0 +1,80 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+
+use crate::error::{AppError, AppResult};
+
+/// Builds an Argon2id hasher with a time cost (iteration count) read from
+/// `HASH_COST`, falling back to 3 (OWASP's minimum recommendation) if unset
+/// or invalid. Lets CI set a cheaper value (e.g. `HASH_COST=1`) so the test
+/// suite doesn't pay production-grade hashing latency on every run.
+fn build_argon2() -> Argon2<'static> {
+    let t_cost = std::env::var("HASH_COST")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(3);
+
+    let params = Params::new(
+        Params::DEFAULT_M_COST,
+        t_cost,
+        Params::DEFAULT_P_COST,
+        None,
+    )
+    .expect("HASH_COST produced invalid Argon2 parameters");
+
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+}
+
+/// Hashes `password` with Argon2id using a fresh random salt, returning the
+/// self-describing PHC string (algorithm, version, params, salt and hash all
+/// encoded together) that should be stored as-is.
+pub fn hash_password(password: &str) -> AppResult<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    build_argon2()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppError::Internal(format!("Failed to hash password: {}", e)))
+}
+
+/// Verifies `password` against a previously stored PHC hash string.
+/// Returns `Ok(false)` for a simple mismatch; `Err` only if `hash` itself is
+/// not a well-formed PHC string (e.g. data corruption).
+pub fn verify_password(password: &str, hash: &str) -> AppResult<bool> {
+    let parsed_hash = PasswordHash::new(hash)
+        .map_err(|e| AppError::Internal(format!("Stored password hash is malformed: {}", e)))?;
+
+    Ok(build_argon2()
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .is_ok())
+}
+
+// --- Unit Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_and_verify_round_trip() {
+        let hash = hash_password("correct horse battery").unwrap();
+        assert!(verify_password("correct horse battery", &hash).unwrap());
+    }
+
+    #[test]
+    fn test_verify_rejects_wrong_password() {
+        let hash = hash_password("correct horse battery").unwrap();
+        assert!(!verify_password("wrong password", &hash).unwrap());
+    }
+
+    #[test]
+    fn test_hash_is_salted_differently_each_time() {
+        let hash1 = hash_password("same password").unwrap();
+        let hash2 = hash_password("same password").unwrap();
+        assert_ne!(hash1, hash2);
+    }
+
+    #[test]
+    fn test_verify_rejects_malformed_hash() {
+        let result = verify_password("anything", "not-a-phc-string");
+        assert!(matches!(result, Err(AppError::Internal(_))));
+    }
+}